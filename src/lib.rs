@@ -3,51 +3,61 @@
 //! `web_ical` is an esay iCalendar Rust library. It’s goals are to read and write ics web files (Google Calendar, Airbnb Calendar and more) data in a developer-friendly way.
 //!
 //! # Examples 1
-//! ```
+//! ```no_run
 //! extern crate web_ical;
 //!
 //!use web_ical::Calendar;
 //!
-//!fn main() {
-//!    let icals = Calendar::new("http://ical.mac.com/ical/US32Holidays.ics").unwrap();
+//!#[tokio::main]
+//!async fn main() {
+//!    let icals = Calendar::new("http://ical.mac.com/ical/US32Holidays.ics").await.unwrap();
 //!
 //!    for ical in &icals.events{
-//!         println!("Event: {}", ical.summary);
-//!         println!("Started: {}", ical.dtstart.format("%a, %e %b %Y - %T"));
+//!         println!("Event: {}", ical.summary.as_deref().unwrap_or_default());
+//!         if let Some(start) = ical.dtstart {
+//!             println!("Started: {}", start.format("%a, %e %b %Y - %T"));
+//!         }
 //!    }
 //!}
 //! ```
 //! # Examples 2
-//! ```
+//! ```no_run
 //! extern crate web_ical;
 //!
 //!use web_ical::Calendar;
 //!
-//!fn main() {
-//!    let icals = Calendar::new("http://ical.mac.com/ical/US32Holidays.ics");
-//!     println!("UTC now is: {}", icals.events[0].dtstart);
-//!     println!("UTC now in RFC 2822 is: {}", icals.events[0].dtstart.to_rfc2822());
-//!     println!("UTC now in RFC 3339 is: {}", icals.events[0].dtstart.to_rfc3339());
-//!     println!("UTC now in a custom format is: {}", icals.events[0].dtstart.format("%a %b %e %T %Y"));
+//!#[tokio::main]
+//!async fn main() {
+//!    let icals = Calendar::new("http://ical.mac.com/ical/US32Holidays.ics").await.unwrap();
+//!     let dtstart = icals.events[0].dtstart.unwrap();
+//!     println!("UTC now is: {}", dtstart);
+//!     println!("UTC now in RFC 2822 is: {}", dtstart.to_rfc2822());
+//!     println!("UTC now in RFC 3339 is: {}", dtstart.to_rfc3339());
+//!     println!("UTC now in a custom format is: {}", dtstart.format("%a %b %e %T %Y"));
 //!}
 //! ```
 extern crate chrono;
 
 use anyhow::Context;
 use chrono::Utc;
-use chrono::{DateTime, NaiveDateTime};
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday,
+};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::{self, Cursor, ErrorKind};
+use std::io::{self, BufReader, BufWriter, Cursor, ErrorKind};
 use std::path::Path;
 
 ///Convert datetime string to [`DateTime`](https://docs.rs/chrono/0.4.7/chrono/struct.DateTime.html)
 ///
 /// # Examples
 ///
-/// ```
-/// let result_obj_aux: Result<DateTime<Utc>, String>;
-/// result_obj_aux = convert_datetime("20190522T232701Z", "%Y%m%dT%H%M%SZ".to_string());
+/// ```ignore
+/// let result_obj_aux: anyhow::Result<DateTime<Utc>>;
+/// result_obj_aux = convert_datetime("20190522T232701Z", "%Y%m%dT%H%M%SZ");
 /// match result_obj_aux{
 ///     Ok(val) => {
 ///             println!("{}", val);
@@ -57,591 +67,7793 @@ use std::path::Path;
 ///```
 fn convert_datetime(value: &str, format: &str) -> anyhow::Result<DateTime<Utc>> {
     let no_timezone_aux = NaiveDateTime::parse_from_str(value, format)?;
-    Ok(DateTime::from_utc(no_timezone_aux, Utc))
-}
-
-///store all events from iCalendar.
-#[derive(Clone)]
-// You should have called it Event, as it is only one event
-pub struct Event {
-    pub dtstamp: Option<DateTime<Utc>>,
-    pub uid: Option<String>,
-    pub dtstart: Option<DateTime<Utc>>,
-    pub dtend: Option<DateTime<Utc>>,
-    pub created: Option<DateTime<Utc>>,
-    pub description: Option<String>,
-    pub last_modified: Option<DateTime<Utc>>,
-    pub location: Option<String>,
-    pub organizer: Option<String>,
-    pub sequence: Option<u32>,
-    pub status: Option<String>,
-    pub summary: Option<String>,
-    pub transp: Option<String>,
-    pub repeat: Option<Repeat>,
-    pub class: Option<String>,
-    pub geo: Option<String>,
-    // pub last_mod: Option<String>,
-    pub priority: Option<String>,
-    pub recur_id: Option<String>,
-    pub url: Option<String>,
-    // missing: duration support,
-    /*
-    attach / attendee / categories / comment /
-                  contact / exdate / rstatus / related /
-                  resources / rdate / x-prop / iana-prop
-     */
+    Ok(DateTime::from_naive_utc_and_offset(no_timezone_aux, Utc))
 }
 
-impl Event {
-    #[inline]
-    fn check_consistency(&self, cal_has_method: bool) -> bool {
-        // if no method is specified on the calendar object, all of it's events have to specify a dtstart
-        self.dtstamp.is_some() && self.uid.is_some() && (cal_has_method || self.dtstart.is_some())
+/// Escape a TEXT value as described in RFC 5545 section 3.3.11.
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            // a CRLF line break is written as a single `\n`
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\r' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
     }
+    escaped
+}
 
-    fn set_dt_start(&mut self, val: &str) -> anyhow::Result<()> {
-        if self.dtstart.is_some() {
-            panic!("Dtstart may not be specified more than once");
+/// Fold a content line so that no physical line exceeds 75 octets, see RFC 5545 section 3.1.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            len = 1;
         }
-        self.dtstart = Some(convert_datetime(
-            &format!("{val}T000000Z"),
-            "%Y%m%dT%H%M%S",
-        )?);
-        Ok(())
+        folded.push(c);
+        len += c.len_utf8();
     }
+    folded
 }
 
-#[derive(Clone)]
-pub struct Repeat {
-    pub freq: String,
-    pub until: Option<DateTime<Utc>>,
+/// Write the content line `line`, folded, followed by its CRLF terminator.
+fn write_property<W: Write>(writer: &mut W, line: &str) -> io::Result<()> {
+    write!(writer, "{}\r\n", fold_line(line))
 }
 
-impl Event {
-    ///Check if the events is all day.
-    pub fn is_all_day(&self) -> Option<bool> {
-        self.dtstart
-            .as_ref()
-            .zip(self.dtend.as_ref())
-            .map(|(start, end)| end.signed_duration_since(start).num_hours() >= 24)
+/// Undo the escaping of a TEXT value.
+fn unescape_text(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') | Some('N') => unescaped.push('\n'),
+                Some(escaped) => unescaped.push(escaped),
+                None => unescaped.push('\\'),
+            },
+            c => unescaped.push(c),
+        }
     }
-    pub fn empty() -> Event {
-        Event {
-            dtstart: None,
-            dtend: None,
-            dtstamp: None,
-            uid: None,
-            created: None,
-            description: None,
-            last_modified: None,
-            location: None,
-            organizer: None,
-            sequence: None,
-            status: None,
-            summary: None,
-            transp: None,
-            repeat: None,
-            class: None,
-            geo: None,
-            priority: None,
-            recur_id: None,
-            url: None,
+    unescaped
+}
+
+/// Split a comma separated list of TEXT values, unescaping each of them.
+fn split_text_list(value: &str) -> Vec<String> {
+    let mut values = vec![];
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') | Some('N') => current.push('\n'),
+                Some(escaped) => current.push(escaped),
+                None => current.push('\\'),
+            },
+            ',' => values.push(std::mem::take(&mut current)),
+            c => current.push(c),
         }
     }
+    values.push(current);
+    values
 }
 
-/// store the iCalendar and add events from struct `Events`.
-#[derive(Clone)]
-pub struct Calendar {
-    pub name: Option<String>,
-    pub prodid: String,
-    pub version: String,
-    pub calscale: Option<String>,
-    pub method: Option<String>,
-    pub x_wr_calname: Option<String>,
-    pub x_wr_timezone: Option<String>,
-    pub events: Vec<Event>,
+/// Parse a DATE value as midnight UTC.
+fn parse_date(value: &str) -> anyhow::Result<DateTime<Utc>> {
+    Ok(day_start(NaiveDate::parse_from_str(value, "%Y%m%d")?))
 }
 
-macro_rules! assign_if_ok {
-    ($lvalue:expr, $rvalue:expr) => {
-        if let Ok(rvalue_ok) = $rvalue {
-            $lvalue = Some(rvalue_ok);
+/// The midnight starting `day`, which is how DATE values are stored.
+fn day_start(day: NaiveDate) -> DateTime<Utc> {
+    DateTime::from_naive_utc_and_offset(day.and_time(NaiveTime::MIN), Utc)
+}
+
+/// The end of something starting at `start` and lasting `length`, durations reaching past the
+/// latest representable time ending there.
+fn end_after(start: DateTime<Utc>, length: Duration) -> DateTime<Utc> {
+    start
+        .checked_add_signed(length)
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// Split the value of DTSTART/DTEND into whether it is a DATE and the value itself.
+fn date_value<'a>(
+    params: &[(String, String)],
+    value: &'a str,
+    options: &ParseOptions,
+) -> (bool, &'a str) {
+    if options.lenient() {
+        // legacy feeds put the value type in front of the value, e.g. `DTSTART:DATE:20190522`
+        if let Some(date) = value.strip_prefix("DATE:") {
+            return (true, date);
         }
-    };
+        if let Some(date_time) = value.strip_prefix("DATE-TIME:") {
+            return (false, date_time);
+        }
+    }
+    let is_date = param(params, "VALUE").is_some_and(|val| val.eq_ignore_ascii_case("DATE"));
+    (is_date, value)
 }
 
-struct CalendarBuilder {
-    prodid: Option<String>,
-    version: Option<String>,
-    calscale: Option<String>,
-    method: Option<String>,
-    x_wr_timezone: Option<String>,
-    x_wr_calname: Option<String>,
-    name: Option<String>,
-    events: Vec<Event>,
+/// Parse a DATE or a DATE-TIME as split by [`date_value`], converting local times in the zone
+/// `tzid` to UTC.
+fn parse_date_or_date_time(
+    is_date: bool,
+    value: &str,
+    tzid: Option<&str>,
+) -> anyhow::Result<DateTime<Utc>> {
+    if is_date {
+        parse_date(value)
+    } else {
+        let time = parse_date_time(value)?;
+        match tzid {
+            Some(tzid) if !value.ends_with('Z') => Ok(local_to_utc(time.naive_utc(), tzid)),
+            _ => Ok(time),
+        }
+    }
 }
 
-fn parse_cal(raw: &str) -> anyhow::Result<Calendar> {
-    let mut raw = Cursor::new(raw);
-    let mut buf = String::new();
+/// The instant of the local time `local` in the zone `tzid`, which may have the `/` prefix of
+/// globally unique ids. Times in zones unknown to the tz database are taken as UTC, and those
+/// skipped by a DST change use the offset before it like RFC 5545 requires.
+fn local_to_utc(local: NaiveDateTime, tzid: &str) -> DateTime<Utc> {
+    match tzid.trim_start_matches('/').parse() {
+        Ok(tz) => local_in(tz, local),
+        Err(_) => DateTime::from_naive_utc_and_offset(local, Utc),
+    }
+}
 
-    raw.read_line(&mut buf)?;
-    // FIXME: handle this gracefully
-    assert_eq!(&buf, "BEGIN:VCALENDAR\r\n");
+/// The instant of the local time `local` in `tz`, see [`local_to_utc`].
+fn local_in(tz: chrono_tz::Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    use chrono::TimeZone;
 
-    // FIXME: put all this into a builder struct!
-    let mut prodid = None;
-    let mut version = None;
-    let mut calscale = None;
-    let mut method = None;
-    let mut name = None;
-    let mut x_wr_calname = None;
-    let mut x_wr_timezone = None;
+    tz.from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            let later = local.checked_add_signed(Duration::hours(1))?;
+            tz.from_local_datetime(&later).earliest()
+        })
+        .map_or_else(
+            || DateTime::from_naive_utc_and_offset(local, Utc),
+            |time| time.with_timezone(&Utc),
+        )
+}
 
-    let mut events = vec![];
-    loop {
-        buf.clear();
-        if raw.read_line(&mut buf)? == 0 {
-            return Err(anyhow::Error::new(io::Error::from(
-                ErrorKind::UnexpectedEof,
-            )));
-        }
-        // remove the new line character
-        if buf.ends_with("\r\n") {
-            buf.pop();
-            buf.pop();
-        } else if buf.ends_with('\n') {
-            buf.pop();
-        } else {
-            unreachable!();
-        }
-        if &buf == "END:VCALENDAR" {
-            // FIXME: error if cursor has more data to read
-            return Ok(Calendar {
-                prodid: prodid.expect("a calendar needs a prodid"),
-                version: version.expect("a calendar needs a version"),
-                calscale,
-                method,
-                x_wr_calname,
-                x_wr_timezone,
-                events,
-                name,
-            });
-        }
-        let (key, value) = if let Some(kv) = buf.split_once(':') {
-            kv
-        } else {
-            println!("Found bad line: {}", buf);
-            continue;
-        };
-        match key {
-            "NAME" => {
-                name = Some(value.to_string());
-            }
-            "PRODID" => {
-                assert!(prodid.is_none());
-                prodid = Some(value.to_string());
-            }
-            "VERSION" => {
-                assert!(version.is_none());
-                version = Some(value.to_string());
-            }
-            "CALSCALE" => {
-                calscale = Some(value.to_string());
-            }
-            "METHOD" => {
-                method = Some(value.to_string());
-            }
-            "X-WR-CALNAME" => {
-                x_wr_calname = Some(value.to_string());
-            }
-            "X-WR-TIMEZONE" => {
-                x_wr_timezone = Some(value.to_string());
-            }
-            "BEGIN" => {
-                if value == "VEVENT" {
-                    events.push(parse_event(&mut raw)?);
-                } else {
-                    // FIXME: todo support this!
+/// Parse the comma-separated DATE or DATE-TIME values of a property like `EXDATE`, malformed
+/// ones being skipped.
+fn parse_date_list<'a>(
+    is_date: bool,
+    value: &'a str,
+    tzid: Option<&'a str>,
+) -> impl Iterator<Item = DateTime<Utc>> + 'a {
+    value
+        .split(',')
+        .filter_map(move |value| parse_date_or_date_time(is_date, value, tzid).ok())
+}
+
+/// Formats of DATE-TIME values without the `Z` suffix, the RFC 5545 one followed by those found
+/// in sloppy feeds.
+const DATE_TIME_FORMATS: &[&str] = &["%Y%m%dT%H%M%S", "%Y%m%dT%H%M%S%.f", "%Y%m%dT%H%M"];
+
+/// Parse a DATE-TIME value given either in UTC (`20190522T232701Z`) or as local time.
+///
+/// Values without seconds or with fractional seconds are accepted as well.
+fn parse_date_time(value: &str) -> anyhow::Result<DateTime<Utc>> {
+    let value = value.strip_suffix('Z').unwrap_or(value);
+    DATE_TIME_FORMATS[1..].iter().fold(
+        convert_datetime(value, DATE_TIME_FORMATS[0]),
+        |result, format| result.or_else(|_| convert_datetime(value, format)),
+    )
+}
+
+/// Split a property's key into its name and its `;`-separated `NAME=VALUE` parameters.
+///
+/// Quoted parameter values may contain `:` and `;` and are returned without their quotes.
+/// Parameter names are case-insensitive and returned in uppercase.
+fn parse_params(key: &str) -> (&str, Vec<(String, String)>) {
+    let (name, rest) = match key.split_once(';') {
+        Some(split) => split,
+        None => return (key, vec![]),
+    };
+    let mut params = vec![];
+    let mut param = String::new();
+    let mut in_quotes = false;
+    for c in rest.chars().chain(std::iter::once(';')) {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                if let Some((name, value)) = param.split_once('=') {
+                    params.push((name.to_ascii_uppercase(), value.to_string()));
                 }
+                param.clear();
             }
-            _ => println!("unknown calendar key value pair \"{key}\": \"{value}\""),
+            c => param.push(c),
         }
     }
+    (name, params)
 }
 
-fn parse_event(raw: &mut Cursor<&str>) -> anyhow::Result<Event> {
-    let mut buf = String::new();
-    let mut ev = Event::empty();
-    loop {
-        buf.clear();
-        if raw.read_line(&mut buf)? == 0 {
-            return Err(anyhow::Error::new(io::Error::from(
-                ErrorKind::UnexpectedEof,
-            )));
-        }
-        // remove the new line character
-        if buf.ends_with("\r\n") {
-            buf.pop();
-            buf.pop();
-        } else if buf.ends_with('\n') {
-            buf.pop();
-        } else {
-            unreachable!();
-        }
-        if &buf == "END:VEVENT" {
-            return Ok(ev);
-        }
-        let (key, value) = if let Some(kv) = buf.split_once(':') {
-            kv
-        } else {
-            println!("Found bad line: {}", buf);
-            continue;
-        };
-        // FIXME: properly handle multi part keys
-        let key = key.split(';').next().unwrap_or(key);
-        match key {
-            "CLASS" => {
-                ev.class = Some(value.to_string());
-            }
-            "GEO" => {
-                ev.geo = Some(value.to_string());
-            }
-            "PRIORITY" => {
-                ev.priority = Some(value.to_string());
-            }
-            "RECUR-ID" => {
-                ev.recur_id = Some(value.to_string());
-            }
-            "URL" => {
-                ev.url = Some(value.to_string());
-            }
-            "UID" => {
-                ev.uid = Some(value.to_string());
-            }
-            "DESCRIPTION" => {
-                ev.description = Some(value.to_string());
-            }
-            "LOCATION" => {
-                ev.location = Some(value.to_string());
-            }
-            "SEQUENCE" => {
-                ev.sequence = Some(value.parse::<u32>().unwrap());
-            }
-            "STATUS" => {
-                ev.status = Some(value.to_string());
-            }
-            "SUMMARY" => {
-                ev.summary = Some(value.to_string());
-            }
-            "TRANSP" => {
-                ev.transp = Some(value.to_string());
-            }
-            "ORGANIZER" => {
-                ev.organizer = Some(value.to_string());
-            }
-            "RRULE" => {
-                let mut vals = value.split(';');
-                // FIXME: can we trust on this order always being this way?
-                let freq = {
-                    let freq = vals.next().unwrap();
-                    if freq.starts_with("FREQ=") {
-                        &freq["FREQ=".len()..]
-                    } else {
-                        println!("Found weird rrule: {}", value);
-                        continue;
-                    }
+/// Split a content line into its key (name and parameters) and its value.
+///
+/// The separating colon is the first one that is not part of a quoted parameter value. Spaces
+/// before it are dropped, with `lenient` a single space after it too, as in `SUMMARY : Meeting`.
+fn split_property(line: &str, lenient: bool) -> Option<(&str, &str)> {
+    let mut in_quotes = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => {
+                let value = &line[i + 1..];
+                let value = match value.strip_prefix(' ') {
+                    Some(value) if lenient => value,
+                    _ => value,
                 };
-                let until = vals
-                    .next()
-                    .map(|until| {
-                        if until.starts_with("UNTIL=") {
-                            match convert_datetime(&freq["UNTIL=".len()..], "%Y%m%dT%H%M%S") {
-                                Ok(val) => Some(val),
-                                Err(_) => None,
-                            }
-                        } else {
-                            println!("Found weird rrule: {}", value);
-                            None
-                        }
-                    })
-                    .flatten();
-                ev.repeat = Some(Repeat {
-                    freq: freq.to_string(),
-                    until,
-                });
-            }
-            "DTSTART" => match convert_datetime(&value, "%Y%m%dT%H%M%S") {
-                Ok(val) => {
-                    ev.dtstart = Some(val);
-                }
-                Err(_) => (),
-            },
-            "DTEND" => match convert_datetime(&value, "%Y%m%dT%H%M%S") {
-                Ok(val) => {
-                    ev.dtend = Some(val);
-                }
-                Err(_) => (),
-            },
-            "DTSTAMP" => {
-                assign_if_ok!(ev.dtstamp, convert_datetime(&value, "%Y%m%dT%H%M%S"));
-            }
-            "CREATED" => {
-                assign_if_ok!(ev.created, convert_datetime(&value, "%Y%m%dT%H%M%S"));
+                return Some((line[..i].trim_end(), value));
             }
-            "LAST-MODIFIED" => {
-                assign_if_ok!(ev.last_modified, convert_datetime(&value, "%Y%m%dT%H%M%S"));
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Look up the value of the parameter `name`.
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(param, _)| param == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Quote a parameter value if it contains characters that are not allowed unquoted.
+fn quote_param(value: &str) -> String {
+    if value.contains([',', ';', ':']) {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Format parameters as they follow a property name, e.g. `;X-ADDRESS="Main St, 1"`.
+fn format_params(params: &[(String, String)]) -> String {
+    params
+        .iter()
+        .map(|(name, value)| format!(";{}={}", name, quote_param(value)))
+        .collect()
+}
+
+/// Decode the `%XX` escapes of a URI component, invalid UTF-8 being replaced.
+fn percent_decode(value: &str) -> String {
+    String::from_utf8_lossy(&decode_hex_escapes(value, b'%')).into_owned()
+}
+
+/// Replace the escapes made of `marker` and two hex digits, `%XX` in URIs and `=XX` in
+/// quoted-printable text, by the bytes they stand for.
+fn decode_hex_escapes(value: &str, marker: u8) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], escaped) {
+            (byte, Some(escaped)) if byte == marker => {
+                decoded.push(escaped);
+                i += 3;
             }
-            other => {
-                println!("unhandled key, value: \"{}\": \"{}\"", other, value);
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
             }
         }
     }
+    decoded
 }
 
-impl Calendar {
-    /// Request HTTP or HTTPS to iCalendar url.
-    pub async fn new(url: &str) -> anyhow::Result<Calendar> {
-        let data = reqwest::get(url)
-            .await
-            .context("Could not make request")?
-            .text()
-            .await
-            .context("Could not read response")?;
-        Self::new_from_data(&data)
+/// Escape everything but the unreserved characters of RFC 3986 as `%XX`.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
     }
+    encoded
+}
 
-    /// Create a `Calendar` from text in memory.
-    pub fn new_from_data(data: &str) -> anyhow::Result<Calendar> {
-        parse_cal(data)
+/// The HTML of a `data:text/html,...` URI as used in `ALTREP` parameters.
+fn html_from_data_uri(uri: &str) -> Option<String> {
+    let scheme = uri.get(.."data:".len())?;
+    if !scheme.eq_ignore_ascii_case("data:") {
+        return None;
     }
-    /// Add events to the calendar.
-    ///
-    /// # Add events
-    /// ```
-    /// let mut start_cal:  DateTime<Utc> = Utc::now();
-    //  let date_tz: DateTime<Utc> = Utc::now();
-    /// let start = date_tz.checked_add_signed(Duration::days(2));
-    ///
-    /// match start {
-    ///       Some(x) => {
-    ///              start_cal = x;
-    ///       },
-    ///       None => ()
-    /// }
-    /// let own_event = Events{
-    ///                    
-    ///                    dtstart:        start_cal,
-    ///                    dtend:          start_cal,
-    ///                    dtstamp:        date_tz,
-    ///                    uid:            "786566jhjh5546@google.com".to_string(),
-    ///                    created:        date_tz,
-    ///                    description:    "The description".to_string(),
-    ///                    last_modified:  date_tz,
-    ///                    location:       "Homestead FL".to_string(),
-    ///                    sequence:       0,
-    ///                    status:         "CONFIRMED".to_string(),
-    ///                    summary:        "My business (Not available)".to_string(),
-    ///                    transp:         "OPAQUE".to_string()
-    ///
-    ///    };
-    /// let mut ical =  Calendar::create(
-    ///                       "-//My Business Inc//My Calendar 70.9054//EN",
-    ///                       "2.0",
-    ///                       "GREGORIAN",
-    ///                       "PUBLISH",
-    ///                       "example@gmail.com",
-    ///                       "America/New_York");
-    ///
-    /// ical.add_event(own_event);
-    /// println!("{}", ical.events[0].summary);
-    ///
-    /// ```
-    pub fn add_event(&mut self, event: Event) {
-        self.events.push(event);
+    let (media_type, data) = uri["data:".len()..].split_once(',')?;
+    let media_type = media_type.to_ascii_lowercase();
+    if !media_type.starts_with("text/html") || media_type.ends_with(";base64") {
+        return None;
     }
+    Some(percent_decode(data))
+}
 
-    /// Export iCalendar to any `Write` implementer.
-    ///
-    /// # iCalendar to stdout
+/// Strip the `mailto:` scheme from a calendar user address.
+fn strip_mailto(value: &str) -> &str {
+    match value.get(.."mailto:".len()) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("mailto:") => &value["mailto:".len()..],
+        _ => value,
+    }
+}
+
+/// Lowercase an address with its `mailto:` scheme stripped, rejecting other schemes.
+fn normalize_email(email: &str) -> Option<String> {
+    let email = strip_mailto(email);
+    if email.contains(':') {
+        None
+    } else {
+        Some(email.to_lowercase())
+    }
+}
+
+/// Turn an email address back into a calendar user address, keeping other schemes as they are.
+fn with_mailto(email: &str) -> String {
+    if email.contains(':') {
+        email.to_string()
+    } else {
+        format!("mailto:{}", email)
+    }
+}
+
+/// Parse an RFC 5545 DURATION value such as `-PT15M`, `P1D` or `P2W`.
+fn parse_duration(value: &str) -> anyhow::Result<Duration> {
+    let (negative, rest) = match value.as_bytes().first() {
+        Some(b'-') => (true, &value[1..]),
+        Some(b'+') => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let rest = rest
+        .strip_prefix('P')
+        .with_context(|| format!("invalid duration: {}", value))?;
+    let mut duration = Duration::zero();
+    let mut in_time = false;
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => in_time = true,
+            unit => {
+                let amount: i64 = number
+                    .parse()
+                    .with_context(|| format!("invalid duration: {}", value))?;
+                number.clear();
+                let part = match (unit, in_time) {
+                    ('W', false) => Duration::try_weeks(amount),
+                    ('D', false) => Duration::try_days(amount),
+                    ('H', true) => Duration::try_hours(amount),
+                    ('M', true) => Duration::try_minutes(amount),
+                    ('S', true) => Duration::try_seconds(amount),
+                    _ => None,
+                };
+                duration = part
+                    .and_then(|part| duration.checked_add(&part))
+                    .with_context(|| format!("invalid duration: {}", value))?;
+            }
+        }
+    }
+    if !number.is_empty() {
+        anyhow::bail!("invalid duration: {}", value);
+    }
+    Ok(if negative { -duration } else { duration })
+}
+
+/// Format a duration as an RFC 5545 DURATION value.
+fn format_duration(duration: Duration) -> String {
+    let mut out = String::new();
+    if duration < Duration::zero() {
+        out.push('-');
+    }
+    let secs = duration.num_seconds().abs();
+    out.push('P');
+    if secs != 0 && secs % (7 * 86400) == 0 {
+        out.push_str(&format!("{}W", secs / (7 * 86400)));
+        return out;
+    }
+    let (days, secs) = (secs / 86400, secs % 86400);
+    if days != 0 {
+        out.push_str(&format!("{}D", days));
+    }
+    if secs != 0 || days == 0 {
+        out.push('T');
+        let (hours, minutes, secs) = (secs / 3600, secs % 3600 / 60, secs % 60);
+        if hours != 0 {
+            out.push_str(&format!("{}H", hours));
+        }
+        if minutes != 0 {
+            out.push_str(&format!("{}M", minutes));
+        }
+        if secs != 0 || (hours == 0 && minutes == 0) {
+            out.push_str(&format!("{}S", secs));
+        }
+    }
+    out
+}
+
+/// Serialize durations as RFC 5545 DURATION values, as chrono doesn't implement serde for them.
+#[cfg(feature = "serde")]
+mod serde_duration {
+    use chrono::Duration;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format_duration(*duration))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        super::parse_duration(&value).map_err(D::Error::custom)
+    }
+
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            duration: &Option<Duration>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match duration {
+                Some(duration) => super::serialize(duration, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Duration>, D::Error> {
+            Option::<String>::deserialize(deserializer)?
+                .map(|value| super::super::parse_duration(&value).map_err(D::Error::custom))
+                .transpose()
+        }
+    }
+}
+
+/// Parse the UNTIL part of a RRULE.
+///
+/// It is a DATE if the event starts on a DATE, in which case the whole last day is included.
+fn parse_until(value: &str) -> anyhow::Result<DateTime<Utc>> {
+    if value.len() == 8 && value.bytes().all(|b| b.is_ascii_digit()) {
+        let day = NaiveDate::parse_from_str(value, "%Y%m%d")?;
+        let end_of_day = day.and_hms_opt(23, 59, 59).context("invalid end of day")?;
+        Ok(DateTime::from_naive_utc_and_offset(end_of_day, Utc))
+    } else {
+        parse_date_time(value)
+    }
+}
+
+/// Parse a two letter weekday of a RRULE, e.g. `MO` or `SU`.
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// The CSS3 color names accepted by the `COLOR` property of RFC 7986, with their RGB values.
+const CSS_COLORS: &[(&str, (u8, u8, u8))] = &[
+    ("aliceblue", (240, 248, 255)),
+    ("antiquewhite", (250, 235, 215)),
+    ("aqua", (0, 255, 255)),
+    ("aquamarine", (127, 255, 212)),
+    ("azure", (240, 255, 255)),
+    ("beige", (245, 245, 220)),
+    ("bisque", (255, 228, 196)),
+    ("black", (0, 0, 0)),
+    ("blanchedalmond", (255, 235, 205)),
+    ("blue", (0, 0, 255)),
+    ("blueviolet", (138, 43, 226)),
+    ("brown", (165, 42, 42)),
+    ("burlywood", (222, 184, 135)),
+    ("cadetblue", (95, 158, 160)),
+    ("chartreuse", (127, 255, 0)),
+    ("chocolate", (210, 105, 30)),
+    ("coral", (255, 127, 80)),
+    ("cornflowerblue", (100, 149, 237)),
+    ("cornsilk", (255, 248, 220)),
+    ("crimson", (220, 20, 60)),
+    ("cyan", (0, 255, 255)),
+    ("darkblue", (0, 0, 139)),
+    ("darkcyan", (0, 139, 139)),
+    ("darkgoldenrod", (184, 134, 11)),
+    ("darkgray", (169, 169, 169)),
+    ("darkgreen", (0, 100, 0)),
+    ("darkgrey", (169, 169, 169)),
+    ("darkkhaki", (189, 183, 107)),
+    ("darkmagenta", (139, 0, 139)),
+    ("darkolivegreen", (85, 107, 47)),
+    ("darkorange", (255, 140, 0)),
+    ("darkorchid", (153, 50, 204)),
+    ("darkred", (139, 0, 0)),
+    ("darksalmon", (233, 150, 122)),
+    ("darkseagreen", (143, 188, 143)),
+    ("darkslateblue", (72, 61, 139)),
+    ("darkslategray", (47, 79, 79)),
+    ("darkslategrey", (47, 79, 79)),
+    ("darkturquoise", (0, 206, 209)),
+    ("darkviolet", (148, 0, 211)),
+    ("deeppink", (255, 20, 147)),
+    ("deepskyblue", (0, 191, 255)),
+    ("dimgray", (105, 105, 105)),
+    ("dimgrey", (105, 105, 105)),
+    ("dodgerblue", (30, 144, 255)),
+    ("firebrick", (178, 34, 34)),
+    ("floralwhite", (255, 250, 240)),
+    ("forestgreen", (34, 139, 34)),
+    ("fuchsia", (255, 0, 255)),
+    ("gainsboro", (220, 220, 220)),
+    ("ghostwhite", (248, 248, 255)),
+    ("gold", (255, 215, 0)),
+    ("goldenrod", (218, 165, 32)),
+    ("gray", (128, 128, 128)),
+    ("green", (0, 128, 0)),
+    ("greenyellow", (173, 255, 47)),
+    ("grey", (128, 128, 128)),
+    ("honeydew", (240, 255, 240)),
+    ("hotpink", (255, 105, 180)),
+    ("indianred", (205, 92, 92)),
+    ("indigo", (75, 0, 130)),
+    ("ivory", (255, 255, 240)),
+    ("khaki", (240, 230, 140)),
+    ("lavender", (230, 230, 250)),
+    ("lavenderblush", (255, 240, 245)),
+    ("lawngreen", (124, 252, 0)),
+    ("lemonchiffon", (255, 250, 205)),
+    ("lightblue", (173, 216, 230)),
+    ("lightcoral", (240, 128, 128)),
+    ("lightcyan", (224, 255, 255)),
+    ("lightgoldenrodyellow", (250, 250, 210)),
+    ("lightgray", (211, 211, 211)),
+    ("lightgreen", (144, 238, 144)),
+    ("lightgrey", (211, 211, 211)),
+    ("lightpink", (255, 182, 193)),
+    ("lightsalmon", (255, 160, 122)),
+    ("lightseagreen", (32, 178, 170)),
+    ("lightskyblue", (135, 206, 250)),
+    ("lightslategray", (119, 136, 153)),
+    ("lightslategrey", (119, 136, 153)),
+    ("lightsteelblue", (176, 196, 222)),
+    ("lightyellow", (255, 255, 224)),
+    ("lime", (0, 255, 0)),
+    ("limegreen", (50, 205, 50)),
+    ("linen", (250, 240, 230)),
+    ("magenta", (255, 0, 255)),
+    ("maroon", (128, 0, 0)),
+    ("mediumaquamarine", (102, 205, 170)),
+    ("mediumblue", (0, 0, 205)),
+    ("mediumorchid", (186, 85, 211)),
+    ("mediumpurple", (147, 112, 219)),
+    ("mediumseagreen", (60, 179, 113)),
+    ("mediumslateblue", (123, 104, 238)),
+    ("mediumspringgreen", (0, 250, 154)),
+    ("mediumturquoise", (72, 209, 204)),
+    ("mediumvioletred", (199, 21, 133)),
+    ("midnightblue", (25, 25, 112)),
+    ("mintcream", (245, 255, 250)),
+    ("mistyrose", (255, 228, 225)),
+    ("moccasin", (255, 228, 181)),
+    ("navajowhite", (255, 222, 173)),
+    ("navy", (0, 0, 128)),
+    ("oldlace", (253, 245, 230)),
+    ("olive", (128, 128, 0)),
+    ("olivedrab", (107, 142, 35)),
+    ("orange", (255, 165, 0)),
+    ("orangered", (255, 69, 0)),
+    ("orchid", (218, 112, 214)),
+    ("palegoldenrod", (238, 232, 170)),
+    ("palegreen", (152, 251, 152)),
+    ("paleturquoise", (175, 238, 238)),
+    ("palevioletred", (219, 112, 147)),
+    ("papayawhip", (255, 239, 213)),
+    ("peachpuff", (255, 218, 185)),
+    ("peru", (205, 133, 63)),
+    ("pink", (255, 192, 203)),
+    ("plum", (221, 160, 221)),
+    ("powderblue", (176, 224, 230)),
+    ("purple", (128, 0, 128)),
+    ("red", (255, 0, 0)),
+    ("rosybrown", (188, 143, 143)),
+    ("royalblue", (65, 105, 225)),
+    ("saddlebrown", (139, 69, 19)),
+    ("salmon", (250, 128, 114)),
+    ("sandybrown", (244, 164, 96)),
+    ("seagreen", (46, 139, 87)),
+    ("seashell", (255, 245, 238)),
+    ("sienna", (160, 82, 45)),
+    ("silver", (192, 192, 192)),
+    ("skyblue", (135, 206, 235)),
+    ("slateblue", (106, 90, 205)),
+    ("slategray", (112, 128, 144)),
+    ("slategrey", (112, 128, 144)),
+    ("snow", (255, 250, 250)),
+    ("springgreen", (0, 255, 127)),
+    ("steelblue", (70, 130, 180)),
+    ("tan", (210, 180, 140)),
+    ("teal", (0, 128, 128)),
+    ("thistle", (216, 191, 216)),
+    ("tomato", (255, 99, 71)),
+    ("turquoise", (64, 224, 208)),
+    ("violet", (238, 130, 238)),
+    ("wheat", (245, 222, 179)),
+    ("white", (255, 255, 255)),
+    ("whitesmoke", (245, 245, 245)),
+    ("yellow", (255, 255, 0)),
+    ("yellowgreen", (154, 205, 50)),
+];
+
+/// Look up the RGB value of a CSS3 color name, ignoring case.
+fn css_color(name: &str) -> Option<(u8, u8, u8)> {
+    CSS_COLORS
+        .iter()
+        .find(|(css, _)| css.eq_ignore_ascii_case(name))
+        .map(|(_, rgb)| *rgb)
+}
+
+/// Parse a `GEO` value into latitude and longitude, which have to be within their valid ranges.
+fn parse_geo(value: &str) -> Option<(f64, f64)> {
+    let (lat, lon) = value.split_once(';')?;
+    let lat: f64 = lat.trim().parse().ok()?;
+    let lon: f64 = lon.trim().parse().ok()?;
+    if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+        Some((lat, lon))
+    } else {
+        None
+    }
+}
+
+/// Check whether a DATE-TIME is floating, i.e. neither UTC nor bound to a `TZID`.
+fn is_floating(params: &[(String, String)], value: &str) -> bool {
+    value.contains('T') && !value.ends_with('Z') && param(params, "TZID").is_none()
+}
+
+///store all events from iCalendar.
+///
+/// Events compare equal field by field, so an exported and parsed again event equals the
+/// original. It is not `Eq` so that properties holding floats can be added later on.
+///
+/// ```
+/// use web_ical::Calendar;
+///
+/// let ical = Calendar::new_from_data(
+///     "BEGIN:VCALENDAR\r\n\
+///      PRODID:-//Test//EN\r\n\
+///      VERSION:2.0\r\n\
+///      BEGIN:VEVENT\r\n\
+///      DTSTART:20190522T090000Z\r\n\
+///      DTEND:20190522T100000Z\r\n\
+///      DTSTAMP:20190501T090000Z\r\n\
+///      UID:1@example.com\r\n\
+///      DESCRIPTION:Weekly sync\r\n\
+///      LOCATION:Office\r\n\
+///      SEQUENCE:3\r\n\
+///      STATUS:CONFIRMED\r\n\
+///      SUMMARY:Sync\r\n\
+///      TRANSP:OPAQUE\r\n\
+///      RRULE:FREQ=WEEKLY;BYDAY=WE\r\n\
+///      CATEGORIES:Work\r\n\
+///      ORGANIZER;CN=The Boss:mailto:boss@x.com\r\n\
+///      X-VENDOR:kept\r\n\
+///      END:VEVENT\r\n\
+///      END:VCALENDAR\r\n",
+/// )
+/// .unwrap();
+///
+/// let rebuilt = Calendar::new_from_data(&ical.to_ics_string()).unwrap();
+/// assert_eq!(rebuilt.events, ical.events);
+/// assert_eq!(rebuilt, ical);
+///
+/// let mut changed = ical.events[0].clone();
+/// changed.sequence = Some(4);
+/// assert_ne!(changed, ical.events[0]);
+/// assert!(format!("{:?}", changed).contains("sequence: Some(4)"));
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+// You should have called it Event, as it is only one event
+pub struct Event {
+    pub dtstamp: Option<DateTime<Utc>>,
+    pub uid: Option<String>,
+    /// The start of the event, also read from sloppy values without seconds or with fractions of
+    /// a second. Local times with a `TZID` of the tz database are converted to UTC, other local
+    /// times are taken as UTC, which applies to the other DATE-TIMEs of the event as well.
+    pub dtstart: Option<DateTime<Utc>>,
+    /// The end of the event, which is exclusive. For all-day events this is the day after their
+    /// last day, see [`Event::inclusive_end_date`].
+    pub dtend: Option<DateTime<Utc>>,
+    /// The length of the event given in `DURATION` instead of a `DTEND`.
+    #[cfg_attr(feature = "serde", serde(default, with = "serde_duration::option"))]
+    pub duration: Option<Duration>,
+    pub created: Option<DateTime<Utc>>,
+    /// The `DESCRIPTION`, already unescaped.
+    pub description: Option<String>,
+    /// The rich text version of the description, given as a `data:text/html,...` URI in the
+    /// `ALTREP` parameter of `DESCRIPTION` as Google does.
+    pub description_html: Option<String>,
+    /// Any other `ALTREP` of `DESCRIPTION`, e.g. a `cid:` or `http:` URI, which is written back as
+    /// found unless there is a `description_html`.
+    pub description_altrep: Option<String>,
+    pub last_modified: Option<DateTime<Utc>>,
+    /// The `LOCATION`, already unescaped.
+    pub location: Option<String>,
+    /// Parameters given on `LOCATION`, e.g. the RFC 7986 style `X-ADDRESS` postal address.
+    pub location_params: Vec<(String, String)>,
+    pub organizer: Option<Organizer>,
+    pub sequence: Option<u32>,
+    pub status: Option<EventStatus>,
+    /// The `SUMMARY`, already unescaped.
+    pub summary: Option<String>,
+    /// The `LANGUAGE` parameter of `SUMMARY`, e.g. `de`.
+    pub summary_language: Option<String>,
+    /// The `LANGUAGE` parameter of `DESCRIPTION`, see [`Event::summary_language`].
+    pub description_language: Option<String>,
+    /// Whether the event blocks time, `OPAQUE` if the feed doesn't say.
+    pub transp: TimeTransparency,
+    /// The recurrence rule given in `RRULE`.
+    pub repeat: Option<Repeat>,
+    /// Extra occurrences given in `RDATE`.
+    pub rdates: Vec<DateTime<Utc>>,
+    /// Occurrences left out of the recurrence, given in `EXDATE`.
+    ///
+    /// Like `RDATE`, a single property may list several comma-separated values.
+    pub exdates: Vec<DateTime<Utc>>,
+    pub class: Option<Classification>,
+    /// Latitude and longitude in degrees given in `GEO`, e.g. `GEO:37.386013;-122.082932`.
+    ///
+    /// Malformed coordinates are skipped, or rejected if [`ParseMode::Strict`] is used.
+    pub geo: Option<(f64, f64)>,
+    /// The `PRIORITY` from 1 (highest) to 9 (lowest), 0 meaning undefined.
+    ///
+    /// Values outside that range are skipped, or rejected if [`ParseMode::Strict`] is used.
+    pub priority: Option<u8>,
+    /// How much of a task-like event is done in percent, 0 to 100, as given by
+    /// `PERCENT-COMPLETE`.
+    pub percent_complete: Option<u8>,
+    /// When a task-like event was completed, as given by `COMPLETED`.
+    pub completed: Option<DateTime<Utc>>,
+    /// The occurrence of a recurring event with the same `UID` which this event replaces, given
+    /// in `RECURRENCE-ID`. The legacy `RECUR-ID` spelling is accepted as well.
+    ///
+    /// [`Calendar::events_between`] shows such an event instead of the occurrence it replaces.
+    pub recur_id: Option<DateTime<Utc>>,
+    /// The `URL` exactly as found in the feed, percent-encoding and all, and written back the
+    /// same way. See [`Event::url_parsed`] to work with it.
+    pub url: Option<String>,
+    /// The CSS3 color name of the event's `COLOR` property as found in the feed, see
+    /// [`Event::color_rgb`].
+    pub color: Option<String>,
+    /// Categories of the event as found in `CATEGORIES:Work,Personal`, already unescaped.
+    pub categories: Vec<String>,
+    /// Contact information given in the `CONTACT` properties, already unescaped.
+    pub contact: Vec<String>,
+    /// Comments given in the `COMMENT` properties, already unescaped.
+    pub comment: Vec<String>,
+    /// Equipment or rooms needed for the event as found in `RESOURCES:Projector,Laptop`, already
+    /// unescaped.
+    pub resources: Vec<String>,
+    /// Participants as found in the `ATTENDEE` properties.
+    pub attendees: Vec<Attendee>,
+    /// Files referenced or embedded by the `ATTACH` properties, see [`Attachment`].
+    pub attachments: Vec<Attachment>,
+    /// Whether `dtstart` or `dtend` was given as a floating local time in the source.
+    pub floating: bool,
+    /// Whether `dtstart` was given as a DATE. `dtend` is then exclusive, i.e. the day after the
+    /// last day of the event, as RFC 5545 defines it.
+    pub all_day: bool,
+    /// TZID the event should preferably be displayed in.
+    ///
+    /// Defaults to the `TZID` of `DTSTART` or else the calendar's `X-WR-TIMEZONE`.
+    pub display_tz: Option<String>,
+    pub alarms: Vec<Alarm>,
+    /// Unrecognized properties such as vendor `X-` extensions, as key (including parameters)
+    /// and value pairs which are written back verbatim.
+    pub extra: Vec<(String, String)>,
+    /// The names of the properties in the order they were parsed in, a `BEGIN` standing for an
+    /// alarm. Only recorded with [`ParseOptions::preserve_order`].
+    pub property_order: Vec<String>,
+}
+
+/// Compares everything but `property_order`, which only affects how the event is written.
+impl PartialEq for Event {
+    fn eq(&self, other: &Event) -> bool {
+        let Event {
+            dtstamp,
+            uid,
+            dtstart,
+            dtend,
+            duration,
+            created,
+            description,
+            description_html,
+            description_altrep,
+            last_modified,
+            location,
+            location_params,
+            organizer,
+            sequence,
+            status,
+            summary,
+            summary_language,
+            description_language,
+            transp,
+            repeat,
+            rdates,
+            exdates,
+            class,
+            geo,
+            priority,
+            percent_complete,
+            completed,
+            recur_id,
+            url,
+            color,
+            categories,
+            contact,
+            comment,
+            resources,
+            attendees,
+            attachments,
+            floating,
+            all_day,
+            display_tz,
+            alarms,
+            extra,
+            property_order: _,
+        } = self;
+        *dtstamp == other.dtstamp
+            && *uid == other.uid
+            && *dtstart == other.dtstart
+            && *dtend == other.dtend
+            && *duration == other.duration
+            && *created == other.created
+            && *description == other.description
+            && *description_html == other.description_html
+            && *description_altrep == other.description_altrep
+            && *last_modified == other.last_modified
+            && *location == other.location
+            && *location_params == other.location_params
+            && *organizer == other.organizer
+            && *sequence == other.sequence
+            && *status == other.status
+            && *summary == other.summary
+            && *summary_language == other.summary_language
+            && *description_language == other.description_language
+            && *transp == other.transp
+            && *repeat == other.repeat
+            && *rdates == other.rdates
+            && *exdates == other.exdates
+            && *class == other.class
+            && *geo == other.geo
+            && *priority == other.priority
+            && *percent_complete == other.percent_complete
+            && *completed == other.completed
+            && *recur_id == other.recur_id
+            && *url == other.url
+            && *color == other.color
+            && *categories == other.categories
+            && *contact == other.contact
+            && *comment == other.comment
+            && *resources == other.resources
+            && *attendees == other.attendees
+            && *attachments == other.attachments
+            && *floating == other.floating
+            && *all_day == other.all_day
+            && *display_tz == other.display_tz
+            && *alarms == other.alarms
+            && *extra == other.extra
+    }
+}
+
+impl Event {
+    /// The names of the required properties this event lacks.
+    fn check_consistency(&self, cal_has_method: bool) -> Vec<&'static str> {
+        let mut missing = vec![];
+        if self.dtstamp.is_none() {
+            missing.push("DTSTAMP");
+        }
+        if self.uid.is_none() {
+            missing.push("UID");
+        }
+        // if no method is specified on the calendar object, all of it's events have to specify a
+        // dtstart, and an end makes no sense without a start either
+        if (!cal_has_method || self.dtend.is_some()) && self.dtstart.is_none() {
+            missing.push("DTSTART");
+        }
+        missing
+    }
+
+    /// Whether `dtend` is before `dtstart`, as found in some malformed feeds.
+    fn ends_before_start(&self) -> bool {
+        matches!((self.dtstart, self.dtend), (Some(start), Some(end)) if end < start)
+    }
+}
+
+impl Event {
+    /// Set the start from a `DTSTART` value, either a date like `20190522`, which makes the
+    /// event an all-day one, or a date and time like `20190522T090000Z`.
+    ///
+    /// Fails if the value is malformed or the event already has a start.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use web_ical::Event;
+    ///
+    /// let mut event = Event::empty();
+    /// event.set_dt_start("20190522").unwrap();
+    /// assert_eq!(event.dtstart, Some(Utc.with_ymd_and_hms(2019, 5, 22, 0, 0, 0).unwrap()));
+    /// assert!(event.all_day);
+    /// assert!(event.set_dt_start("20190523").is_err());
+    ///
+    /// let mut event = Event::empty();
+    /// event.set_dt_start("20190522T093000Z").unwrap();
+    /// assert_eq!(event.dtstart, Some(Utc.with_ymd_and_hms(2019, 5, 22, 9, 30, 0).unwrap()));
+    /// assert!(!event.all_day);
+    /// assert!(!event.floating);
+    ///
+    /// assert!(Event::empty().set_dt_start("2019-05-22").is_err());
+    /// ```
+    pub fn set_dt_start(&mut self, val: &str) -> anyhow::Result<()> {
+        if self.dtstart.is_some() {
+            anyhow::bail!("Dtstart may not be specified more than once");
+        }
+        let is_date = !val.contains('T');
+        self.dtstart = Some(parse_date_or_date_time(is_date, val, None)?);
+        self.all_day = is_date;
+        self.floating |= is_floating(&[], val);
+        Ok(())
+    }
+}
+
+/// The organizer of an event as found in an `ORGANIZER` property.
+///
+/// ```
+/// use web_ical::Calendar;
+///
+/// let ical = Calendar::new_from_data(
+///     "BEGIN:VCALENDAR\r\n\
+///      PRODID:-//Test//EN\r\n\
+///      VERSION:2.0\r\n\
+///      BEGIN:VEVENT\r\n\
+///      DTSTART:20190522T090000Z\r\n\
+///      DTEND:20190522T100000Z\r\n\
+///      DTSTAMP:20190501T090000Z\r\n\
+///      UID:1@example.com\r\n\
+///      CREATED:20190501T090000Z\r\n\
+///      DESCRIPTION:Planning\r\n\
+///      LAST-MODIFIED:20190501T090000Z\r\n\
+///      LOCATION:Office\r\n\
+///      SEQUENCE:0\r\n\
+///      STATUS:CONFIRMED\r\n\
+///      SUMMARY:Planning\r\n\
+///      TRANSP:OPAQUE\r\n\
+///      ORGANIZER;CN=The Boss:mailto:boss@x.com\r\n\
+///      END:VEVENT\r\n\
+///      END:VCALENDAR\r\n",
+/// )
+/// .unwrap();
+/// let organizer = ical.events[0].organizer.as_ref().unwrap();
+/// assert_eq!(organizer.cn.as_deref(), Some("The Boss"));
+/// assert_eq!(organizer.email, "boss@x.com");
+/// assert_eq!(organizer.to_value(), "mailto:boss@x.com");
+///
+/// let mut out = Vec::new();
+/// ical.export_to(&mut out).unwrap();
+/// let out = String::from_utf8(out).unwrap();
+/// assert!(out.contains("ORGANIZER;CN=The Boss:mailto:boss@x.com\r\n"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Organizer {
+    pub cn: Option<String>,
+    /// The calendar user address without the `mailto:` scheme.
+    pub email: String,
+}
+
+impl Organizer {
+    fn parse(params: &[(String, String)], value: &str) -> Organizer {
+        Organizer {
+            cn: param(params, "CN").map(str::to_string),
+            email: strip_mailto(value).to_string(),
+        }
+    }
+
+    /// The property value rebuilt from `email`, e.g. `mailto:boss@x.com`. Addresses with a
+    /// scheme other than `mailto:` are kept as they are.
+    pub fn to_value(&self) -> String {
+        with_mailto(&self.email)
+    }
+
+    /// The email address in lowercase, `None` if the address uses a scheme other than `mailto:`.
+    pub fn email(&self) -> Option<String> {
+        normalize_email(&self.email)
+    }
+
+    fn to_property(&self) -> String {
+        match self.cn.as_ref() {
+            Some(cn) => format!("ORGANIZER;CN={}:{}", quote_param(cn), self.to_value()),
+            None => format!("ORGANIZER:{}", self.to_value()),
+        }
+    }
+}
+
+/// A participant of an event as found in an `ATTENDEE` property.
+///
+/// ```
+/// use web_ical::Calendar;
+///
+/// let ical = Calendar::new_from_data(
+///     "BEGIN:VCALENDAR\r\n\
+///      PRODID:-//Test//EN\r\n\
+///      VERSION:2.0\r\n\
+///      BEGIN:VEVENT\r\n\
+///      UID:1@example.com\r\n\
+///      ATTENDEE;CN=\"Doe, John: Jr.\";PARTSTAT=TENTATIVE:mailto:john@x.com\r\n\
+///      END:VEVENT\r\n\
+///      END:VCALENDAR\r\n",
+/// )
+/// .unwrap();
+/// let attendee = &ical.events[0].attendees[0];
+/// assert_eq!(attendee.cn.as_deref(), Some("Doe, John: Jr."));
+/// assert_eq!(attendee.partstat.as_deref(), Some("TENTATIVE"));
+/// assert_eq!(attendee.email, "john@x.com");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attendee {
+    pub cn: Option<String>,
+    pub role: Option<String>,
+    pub partstat: Option<String>,
+    pub rsvp: Option<bool>,
+    /// The calendar user address without the `mailto:` scheme.
+    pub email: String,
+}
+
+impl Attendee {
+    /// The email address in lowercase, `None` if the address uses a scheme other than `mailto:`.
+    pub fn email(&self) -> Option<String> {
+        normalize_email(&self.email)
+    }
+
+    fn parse(params: &[(String, String)], value: &str) -> Attendee {
+        let mut attendee = Attendee {
+            cn: None,
+            role: None,
+            partstat: None,
+            rsvp: None,
+            email: strip_mailto(value).to_string(),
+        };
+        for (name, val) in params {
+            match name.as_str() {
+                "CN" => attendee.cn = Some(val.to_string()),
+                "ROLE" => attendee.role = Some(val.to_string()),
+                "PARTSTAT" => attendee.partstat = Some(val.to_string()),
+                "RSVP" => attendee.rsvp = Some(val.eq_ignore_ascii_case("TRUE")),
+                _ => (),
+            }
+        }
+        attendee
+    }
+
+    fn to_property(&self) -> String {
+        let mut line = "ATTENDEE".to_string();
+        if let Some(cn) = self.cn.as_ref() {
+            line.push_str(";CN=");
+            line.push_str(&quote_param(cn));
+        }
+        if let Some(role) = self.role.as_ref() {
+            line.push_str(";ROLE=");
+            line.push_str(role);
+        }
+        if let Some(partstat) = self.partstat.as_ref() {
+            line.push_str(";PARTSTAT=");
+            line.push_str(partstat);
+        }
+        if let Some(rsvp) = self.rsvp {
+            line.push_str(if rsvp { ";RSVP=TRUE" } else { ";RSVP=FALSE" });
+        }
+        line.push(':');
+        line.push_str(&with_mailto(&self.email));
+        line
+    }
+}
+
+/// A file referenced or embedded by an `ATTACH` property.
+///
+/// Binary attachments keep their base64 text, with the `base64` feature it can be decoded by
+/// `Attachment::decode`.
+///
+/// ```
+/// use web_ical::{Attachment, Calendar};
+///
+/// let ical = Calendar::new_from_data(
+///     "BEGIN:VCALENDAR\r\n\
+///      PRODID:-//Test//EN\r\n\
+///      VERSION:2.0\r\n\
+///      BEGIN:VEVENT\r\n\
+///      DTSTART:20190522T090000Z\r\n\
+///      DTEND:20190522T100000Z\r\n\
+///      DTSTAMP:20190501T090000Z\r\n\
+///      UID:1@example.com\r\n\
+///      DESCRIPTION:Review\r\n\
+///      LOCATION:Office\r\n\
+///      SEQUENCE:0\r\n\
+///      STATUS:CONFIRMED\r\n\
+///      SUMMARY:Review\r\n\
+///      TRANSP:OPAQUE\r\n\
+///      ATTACH:https://example.com/agenda.pdf\r\n\
+///      ATTACH;FMTTYPE=image/png;ENCODING=BASE64;VALUE=BINARY:iVBORw0KGgoAAAANSUhEUgAAAAEAA\r\n \
+///      AABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==\r\n\
+///      END:VEVENT\r\n\
+///      END:VCALENDAR\r\n",
+/// )
+/// .unwrap();
+/// let event = &ical.events[0];
+/// assert_eq!(
+///     event.attachments[0],
+///     Attachment::Uri("https://example.com/agenda.pdf".to_string())
+/// );
+/// match &event.attachments[1] {
+///     Attachment::Binary { mime, base64 } => {
+///         assert_eq!(mime.as_deref(), Some("image/png"));
+///         assert!(base64.starts_with("iVBOR"));
+///     }
+///     other => panic!("unexpected attachment: {:?}", other),
+/// }
+/// #[cfg(feature = "base64")]
+/// {
+///     let data = event.attachments[1].decode().unwrap();
+///     assert_eq!(data.len(), 70);
+///     assert!(data.starts_with(b"\x89PNG"));
+/// }
+///
+/// let out = ical.to_ics_string();
+/// assert!(out.contains("ATTACH:https://example.com/agenda.pdf\r\n"));
+/// assert!(out.contains("ATTACH;FMTTYPE=image/png;ENCODING=BASE64;VALUE=BINARY:iVBOR"));
+/// assert_eq!(Calendar::new_from_data(&out).unwrap().events[0].attachments, event.attachments);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Attachment {
+    /// A link to the file, e.g. `ATTACH:https://example.com/agenda.pdf`.
+    Uri(String),
+    /// The file itself, given with `ENCODING=BASE64;VALUE=BINARY` and its media type in
+    /// `FMTTYPE`. `base64` is the value as found, it is not checked.
+    Binary {
+        mime: Option<String>,
+        base64: String,
+    },
+}
+
+impl Attachment {
+    fn parse(params: &[(String, String)], value: &str) -> Attachment {
+        let binary =
+            param(params, "ENCODING").is_some_and(|enc| enc.eq_ignore_ascii_case("BASE64"));
+        if binary {
+            Attachment::Binary {
+                mime: param(params, "FMTTYPE").map(str::to_string),
+                base64: value.to_string(),
+            }
+        } else {
+            Attachment::Uri(value.to_string())
+        }
+    }
+
+    /// A binary attachment holding `data`.
+    #[cfg(feature = "base64")]
+    pub fn from_bytes(mime: Option<String>, data: &[u8]) -> Attachment {
+        use base64::Engine;
+
+        Attachment::Binary {
+            mime,
+            base64: base64::engine::general_purpose::STANDARD.encode(data),
+        }
+    }
+
+    /// The content of a binary attachment, `None` for links and invalid base64.
+    #[cfg(feature = "base64")]
+    pub fn decode(&self) -> Option<Vec<u8>> {
+        use base64::Engine;
+
+        match self {
+            Attachment::Uri(_) => None,
+            Attachment::Binary { base64, .. } => base64::engine::general_purpose::STANDARD
+                .decode(base64)
+                .ok(),
+        }
+    }
+
+    fn to_property(&self) -> String {
+        match self {
+            Attachment::Uri(uri) => format!("ATTACH:{}", uri),
+            Attachment::Binary { mime, base64 } => {
+                let mut line = "ATTACH".to_string();
+                if let Some(mime) = mime {
+                    line.push_str(";FMTTYPE=");
+                    line.push_str(&quote_param(mime));
+                }
+                line.push_str(";ENCODING=BASE64;VALUE=BINARY:");
+                line.push_str(base64);
+                line
+            }
+        }
+    }
+}
+
+/// The `FREQ` of a recurrence rule, from the finest to the coarsest.
+///
+/// ```
+/// use web_ical::Frequency;
+///
+/// let all = ["SECONDLY", "MINUTELY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY"];
+/// for name in all.iter() {
+///     let freq: Frequency = name.parse().unwrap();
+///     assert_eq!(freq.to_string(), *name);
+/// }
+/// assert_eq!("weekly".parse::<Frequency>().unwrap(), Frequency::Weekly);
+/// assert!("FORTNIGHTLY".parse::<Frequency>().is_err());
+/// assert!(Frequency::Daily < Frequency::Weekly);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "UPPERCASE"))]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Frequency::Secondly => "SECONDLY",
+            Frequency::Minutely => "MINUTELY",
+            Frequency::Hourly => "HOURLY",
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        })
+    }
+}
+
+impl std::str::FromStr for Frequency {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> anyhow::Result<Frequency> {
+        Ok(match value.to_ascii_uppercase().as_str() {
+            "SECONDLY" => Frequency::Secondly,
+            "MINUTELY" => Frequency::Minutely,
+            "HOURLY" => Frequency::Hourly,
+            "DAILY" => Frequency::Daily,
+            "WEEKLY" => Frequency::Weekly,
+            "MONTHLY" => Frequency::Monthly,
+            "YEARLY" => Frequency::Yearly,
+            _ => anyhow::bail!("unknown frequency: {}", value),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Repeat {
+    pub freq: Frequency,
+    /// Last instant of the recurrence, a date-only `UNTIL` ends at the end of that day (UTC).
+    pub until: Option<DateTime<Utc>>,
+    pub count: Option<u32>,
+    pub interval: Option<u32>,
+    pub by_second: Vec<u32>,
+    pub by_minute: Vec<u32>,
+    pub by_hour: Vec<u32>,
+    /// Weekdays, optionally prefixed with an ordinal, e.g. `MO` or `-1FR`.
+    pub by_day: Vec<String>,
+    pub by_month_day: Vec<i32>,
+    pub by_year_day: Vec<i32>,
+    pub by_week_no: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub wkst: Option<String>,
+}
+
+impl Repeat {
+    /// Create a rule repeating with the given frequency and no further restrictions.
+    pub fn new(freq: Frequency) -> Repeat {
+        Repeat {
+            freq,
+            until: None,
+            count: None,
+            interval: None,
+            by_second: vec![],
+            by_minute: vec![],
+            by_hour: vec![],
+            by_day: vec![],
+            by_month_day: vec![],
+            by_year_day: vec![],
+            by_week_no: vec![],
+            by_month: vec![],
+            by_set_pos: vec![],
+            wkst: None,
+        }
+    }
+
+    /// Parse an `RRULE` value, its parts may come in any order and their names and keywords are
+    /// matched case-insensitively.
+    ///
+    /// Parts without a value are skipped. Invalid `UNTIL`, `COUNT` and `INTERVAL` parts are left
+    /// out as well, or rejected if [`ParseMode::Strict`] is used, as they change which
+    /// occurrences there are.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTART:20190506T090000Z\r\n\
+    ///      rrule:freq=weekly;until=20190515t235959z;byday=mo,we;wkst=mo\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let repeat = ical.events[0].repeat.as_ref().unwrap();
+    /// assert_eq!(repeat.to_rrule(), "FREQ=WEEKLY;UNTIL=20190515T235959Z;BYDAY=MO,WE;WKST=MO");
+    /// assert_eq!(ical.events[0].first_n_occurrences(10).len(), 4);
+    /// ```
+    fn parse(
+        value: &str,
+        options: &ParseOptions,
+        warnings: &mut Vec<Warning>,
+    ) -> anyhow::Result<Option<Repeat>> {
+        fn list<T: std::str::FromStr>(value: &str) -> Vec<T> {
+            value
+                .split(',')
+                .filter_map(|val| val.parse().ok())
+                .collect()
+        }
+
+        fn bound<T>(
+            value: Option<T>,
+            part: &str,
+            options: &ParseOptions,
+            warnings: &mut Vec<Warning>,
+        ) -> anyhow::Result<Option<T>> {
+            if value.is_none() {
+                if options.strict() {
+                    anyhow::bail!("invalid rrule part: {}", part);
+                }
+                warnings.push(Warning::new(format!("invalid rrule part: {}", part)));
+            }
+            Ok(value)
+        }
+
+        // all parts are either numbers, dates or keywords, the latter being case-insensitive
+        let value = value.to_ascii_uppercase();
+        let mut freq = None;
+        let mut repeat = Repeat::new(Frequency::Daily);
+        for part in value.split(';').filter(|part| !part.is_empty()) {
+            let (name, val) = match part.split_once('=') {
+                Some(split) => split,
+                None => {
+                    warnings.push(Warning::new(format!(
+                        "rrule part without a value: {}",
+                        part
+                    )));
+                    continue;
+                }
+            };
+            match name {
+                "FREQ" => match val.parse::<Frequency>() {
+                    Ok(val) => freq = Some(val),
+                    Err(err) => warnings.push(Warning::new(err.to_string())),
+                },
+                "UNTIL" => repeat.until = bound(parse_until(val).ok(), part, options, warnings)?,
+                "COUNT" => repeat.count = bound(val.parse().ok(), part, options, warnings)?,
+                "INTERVAL" => repeat.interval = bound(val.parse().ok(), part, options, warnings)?,
+                "BYSECOND" => repeat.by_second = list(val),
+                "BYMINUTE" => repeat.by_minute = list(val),
+                "BYHOUR" => repeat.by_hour = list(val),
+                "BYDAY" => repeat.by_day = list(val),
+                "BYMONTHDAY" => repeat.by_month_day = list(val),
+                "BYYEARDAY" => repeat.by_year_day = list(val),
+                "BYWEEKNO" => repeat.by_week_no = list(val),
+                "BYMONTH" => repeat.by_month = list(val),
+                "BYSETPOS" => repeat.by_set_pos = list(val),
+                "WKST" => repeat.wkst = Some(val.to_string()),
+                _ => warnings.push(Warning::new(format!("unhandled rrule part: {}", part))),
+            }
+        }
+        Ok(freq.map(|freq| Repeat { freq, ..repeat }))
+    }
+
+    /// The `RRULE` value with its parts in the order of RFC 5545, omitting unset ones. `UNTIL` is
+    /// written as a UTC DATE-TIME, events starting on a DATE export it as a DATE instead.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      RRULE:WKST=SU;BYDAY=MO,WE;INTERVAL=2;COUNT=10;FREQ=WEEKLY\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let repeat = ical.events[0].repeat.as_ref().unwrap();
+    /// assert_eq!(
+    ///     repeat.to_rrule(),
+    ///     "FREQ=WEEKLY;COUNT=10;INTERVAL=2;BYDAY=MO,WE;WKST=SU"
+    /// );
+    /// ```
+    pub fn to_rrule(&self) -> String {
+        self.rrule_value(false)
+    }
+
+    /// The `RRULE` value, with `UNTIL` as a DATE if `date` is set, as RFC 5545 requires it to
+    /// have the same value type as `DTSTART`.
+    fn rrule_value(&self, date: bool) -> String {
+        fn join<T: ToString>(values: &[T]) -> String {
+            values
+                .iter()
+                .map(|val| val.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        }
+
+        let mut parts = vec![format!("FREQ={}", self.freq)];
+        if let Some(until) = self.until.as_ref() {
+            let format = if date { "%Y%m%d" } else { "%Y%m%dT%H%M%SZ" };
+            parts.push(format!("UNTIL={}", until.format(format)));
+        }
+        if let Some(count) = self.count {
+            parts.push(format!("COUNT={}", count));
+        }
+        if let Some(interval) = self.interval {
+            parts.push(format!("INTERVAL={}", interval));
+        }
+        let by_rules = [
+            ("BYSECOND", join(&self.by_second)),
+            ("BYMINUTE", join(&self.by_minute)),
+            ("BYHOUR", join(&self.by_hour)),
+            ("BYDAY", join(&self.by_day)),
+            ("BYMONTHDAY", join(&self.by_month_day)),
+            ("BYYEARDAY", join(&self.by_year_day)),
+            ("BYWEEKNO", join(&self.by_week_no)),
+            ("BYMONTH", join(&self.by_month)),
+            ("BYSETPOS", join(&self.by_set_pos)),
+        ];
+        for (name, values) in by_rules {
+            if !values.is_empty() {
+                parts.push(format!("{}={}", name, values));
+            }
+        }
+        if let Some(wkst) = self.wkst.as_ref() {
+            parts.push(format!("WKST={}", wkst));
+        }
+        parts.join(";")
+    }
+}
+
+/// Formats the rule as its `RRULE` value, like [`Repeat::to_rrule`] does.
+///
+/// ```
+/// use web_ical::{Frequency, Repeat};
+///
+/// let mut repeat = Repeat::new(Frequency::Weekly);
+/// repeat.interval = Some(2);
+/// repeat.by_day = vec!["TU".to_string(), "TH".to_string()];
+/// assert_eq!(repeat.to_string(), "FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH");
+/// ```
+impl fmt::Display for Repeat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_rrule())
+    }
+}
+
+/// Give up on a recurrence rule after this many periods in a row without an occurrence, so
+/// rules which can never match, e.g. `BYMONTH=2;BYMONTHDAY=30`, end.
+const MAX_EMPTY_PERIODS: u32 = 100_000;
+
+/// Stop expanding `SECONDLY` and `MINUTELY` rules after this many periods, as such a rule
+/// without an end yields millions of occurrences within a few weeks.
+const MAX_FINE_PERIODS: i64 = 100_000;
+
+/// Parse a `BYDAY` entry into its ordinal, `0` if there is none, and weekday, e.g. `-1FR`.
+fn parse_by_day(value: &str) -> Option<(i32, Weekday)> {
+    let split = value.len().checked_sub(2)?;
+    let weekday = parse_weekday(value.get(split..)?)?;
+    let ordinal = match value.get(..split)? {
+        "" => 0,
+        ordinal => ordinal.trim_start_matches('+').parse().ok()?,
+    };
+    Some((ordinal, weekday))
+}
+
+/// The last day of a month, `None` if the year is out of range.
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()
+}
+
+/// Pick the days of `span` matching the `BYDAY` entries, an ordinal counts the matching weekdays
+/// within the whole span.
+fn weekdays_in(span: &[NaiveDate], by_day: &[(i32, Weekday)]) -> Vec<NaiveDate> {
+    let mut dates = vec![];
+    for &(ordinal, weekday) in by_day {
+        let matching: Vec<NaiveDate> = span
+            .iter()
+            .copied()
+            .filter(|date| date.weekday() == weekday)
+            .collect();
+        let index = match ordinal {
+            0 => {
+                dates.extend(matching);
+                continue;
+            }
+            ordinal if ordinal > 0 => ordinal as usize - 1,
+            ordinal => match matching.len().checked_sub(ordinal.unsigned_abs() as usize) {
+                Some(index) => index,
+                None => continue,
+            },
+        };
+        dates.extend(matching.get(index));
+    }
+    dates
+}
+
+/// All days from `first` to `last` inclusive.
+fn days_between(first: NaiveDate, last: NaiveDate) -> Vec<NaiveDate> {
+    first.iter_days().take_while(|date| *date <= last).collect()
+}
+
+impl Repeat {
+    fn by_day_entries(&self) -> Vec<(i32, Weekday)> {
+        self.by_day
+            .iter()
+            .filter_map(|day| parse_by_day(day))
+            .collect()
+    }
+
+    /// Check the `BYMONTH`, `BYMONTHDAY` and `BYDAY` parts which limit daily and finer rules.
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let month_day_matches = |day: &i32| match last_day_of_month(date.year(), date.month()) {
+            Some(last) if *day < 0 => last.day() as i32 + 1 + day == date.day() as i32,
+            _ => *day == date.day() as i32,
+        };
+        (self.by_month.is_empty() || self.by_month.contains(&date.month()))
+            && (self.by_month_day.is_empty() || self.by_month_day.iter().any(month_day_matches))
+            && (self.by_day.is_empty()
+                || self
+                    .by_day_entries()
+                    .iter()
+                    .any(|(_, weekday)| *weekday == date.weekday()))
+    }
+
+    /// The days of a month selected by `BYMONTHDAY` and `BYDAY`, or `default_day` if there are
+    /// neither.
+    fn days_in_month(&self, year: i32, month: u32, default_day: u32) -> Vec<NaiveDate> {
+        let (first, last) = match (
+            NaiveDate::from_ymd_opt(year, month, 1),
+            last_day_of_month(year, month),
+        ) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return vec![],
+        };
+        let by_day = self.by_day_entries();
+        if !self.by_month_day.is_empty() {
+            self.by_month_day
+                .iter()
+                .filter_map(|&day| {
+                    let day = if day < 0 {
+                        last.day() as i32 + 1 + day
+                    } else {
+                        day
+                    };
+                    NaiveDate::from_ymd_opt(year, month, u32::try_from(day).ok()?)
+                })
+                .filter(|date| {
+                    by_day.is_empty() || by_day.iter().any(|(_, wd)| *wd == date.weekday())
+                })
+                .collect()
+        } else if !by_day.is_empty() {
+            weekdays_in(&days_between(first, last), &by_day)
+        } else {
+            NaiveDate::from_ymd_opt(year, month, default_day)
+                .into_iter()
+                .collect()
+        }
+    }
+
+    /// The days of the `offset`th period after the one of `start`, `start` being the day of the
+    /// period itself for sub-daily rules. `None` if that period is out of the range of dates.
+    fn period_days(&self, start: NaiveDate, offset: i64) -> Option<Vec<NaiveDate>> {
+        Some(match self.freq {
+            Frequency::Secondly | Frequency::Minutely | Frequency::Hourly => Some(start)
+                .filter(|date| self.matches_day(*date))
+                .into_iter()
+                .collect(),
+            Frequency::Daily => Some(start.checked_add_signed(Duration::try_days(offset)?)?)
+                .filter(|date| self.matches_day(*date))
+                .into_iter()
+                .collect(),
+            Frequency::Weekly => {
+                let wkst = self
+                    .wkst
+                    .as_deref()
+                    .and_then(parse_weekday)
+                    .unwrap_or(Weekday::Mon);
+                let since_wkst = |weekday: Weekday| {
+                    (weekday.num_days_from_monday() + 7 - wkst.num_days_from_monday()) % 7
+                };
+                let week = start
+                    .checked_sub_signed(Duration::days(since_wkst(start.weekday()).into()))?
+                    .checked_add_signed(Duration::try_weeks(offset)?)?;
+                let mut weekdays: Vec<Weekday> = self
+                    .by_day_entries()
+                    .into_iter()
+                    .map(|(_, wd)| wd)
+                    .collect();
+                if weekdays.is_empty() {
+                    weekdays.push(start.weekday());
+                }
+                weekdays
+                    .into_iter()
+                    .filter_map(|wd| week.checked_add_signed(Duration::days(since_wkst(wd).into())))
+                    .filter(|date| {
+                        self.by_month.is_empty() || self.by_month.contains(&date.month())
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let months = (i64::from(start.year()) * 12 + i64::from(start.month0()))
+                    .checked_add(offset)?;
+                let year = i32::try_from(months.div_euclid(12)).ok()?;
+                let month = months.rem_euclid(12) as u32 + 1;
+                NaiveDate::from_ymd_opt(year, month, 1)?;
+                if !self.by_month.is_empty() && !self.by_month.contains(&month) {
+                    return Some(vec![]);
+                }
+                self.days_in_month(year, month, start.day())
+            }
+            Frequency::Yearly => {
+                let year = i32::try_from(i64::from(start.year()).checked_add(offset)?).ok()?;
+                let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                let last = NaiveDate::from_ymd_opt(year, 12, 31)?;
+                if !self.by_year_day.is_empty() {
+                    let days = self.by_year_day.iter().filter_map(|&day| {
+                        if day < 0 {
+                            last.checked_add_signed(Duration::days(i64::from(day) + 1))
+                        } else {
+                            first.checked_add_signed(Duration::days(i64::from(day) - 1))
+                        }
+                    });
+                    days.filter(|date| date.year() == year && self.matches_day(*date))
+                        .collect()
+                } else if self.by_month.is_empty()
+                    && self.by_month_day.is_empty()
+                    && !self.by_day.is_empty()
+                {
+                    weekdays_in(&days_between(first, last), &self.by_day_entries())
+                } else {
+                    let months = if !self.by_month.is_empty() {
+                        self.by_month.clone()
+                    } else if !self.by_month_day.is_empty() {
+                        (1..=12).collect()
+                    } else {
+                        vec![start.month()]
+                    };
+                    months
+                        .into_iter()
+                        .flat_map(|month| self.days_in_month(year, month, start.day()))
+                        .collect()
+                }
+            }
+        })
+    }
+
+    /// The local starts of the `offset`th period after the one of `dtstart`, in order. `None` if
+    /// that period is out of the range of dates.
+    fn period_starts(&self, dtstart: NaiveDateTime, offset: i64) -> Option<Vec<NaiveDateTime>> {
+        let step = match self.freq {
+            Frequency::Secondly => Duration::try_seconds(offset),
+            Frequency::Minutely => Duration::try_minutes(offset),
+            Frequency::Hourly => Duration::try_hours(offset),
+            Frequency::Daily | Frequency::Weekly | Frequency::Monthly | Frequency::Yearly => {
+                Some(Duration::zero())
+            }
+        };
+        let base = dtstart.checked_add_signed(step?)?;
+        let days = self.period_days(base.date(), offset)?;
+
+        // finer parts than the frequency expand the period, coarser ones limit it
+        let unit = |unit: Frequency, by_unit: &[u32], value: u32| -> Vec<u32> {
+            if by_unit.is_empty() {
+                vec![value]
+            } else if self.freq > unit {
+                by_unit.to_vec()
+            } else if by_unit.contains(&value) {
+                vec![value]
+            } else {
+                vec![]
+            }
+        };
+        let hours = unit(Frequency::Hourly, &self.by_hour, base.hour());
+        let minutes = unit(Frequency::Minutely, &self.by_minute, base.minute());
+        let seconds = unit(Frequency::Secondly, &self.by_second, base.second());
+
+        let mut starts = vec![];
+        for day in &days {
+            for hour in &hours {
+                for minute in &minutes {
+                    for second in &seconds {
+                        if let Some(time) = day.and_hms_opt(*hour, *minute, *second) {
+                            starts.push(time);
+                        }
+                    }
+                }
+            }
+        }
+        starts.sort();
+        starts.dedup();
+        if self.by_set_pos.is_empty() {
+            return Some(starts);
+        }
+        let mut picked: Vec<NaiveDateTime> = self
+            .by_set_pos
+            .iter()
+            .filter_map(|&pos| {
+                let index = if pos < 0 {
+                    starts.len().checked_sub(pos.unsigned_abs() as usize)?
+                } else {
+                    usize::try_from(pos).ok()?.checked_sub(1)?
+                };
+                starts.get(index).copied()
+            })
+            .collect();
+        picked.sort();
+        picked.dedup();
+        Some(picked)
+    }
+}
+
+/// The starts of the occurrences of an event in order, see [`Event::occurrences`].
+pub struct Occurrences<'a> {
+    dtstart: Option<DateTime<Utc>>,
+    /// The zone the rule is expanded in, UTC if `None`.
+    tz: Option<chrono_tz::Tz>,
+    repeat: Option<&'a Repeat>,
+    period: i64,
+    pending: std::collections::VecDeque<DateTime<Utc>>,
+    emitted: u32,
+    empty_periods: u32,
+    /// The `RDATE`s still to come, latest first.
+    rdates: Vec<DateTime<Utc>>,
+    exdates: &'a [DateTime<Utc>],
+    next_rule_start: Option<DateTime<Utc>>,
+}
+
+impl<'a> Occurrences<'a> {
+    /// `time` as the local time the rule is expanded in.
+    fn local(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self.tz {
+            Some(tz) => time.with_timezone(&tz).naive_local(),
+            None => time.naive_utc(),
+        }
+    }
+
+    /// The next start given by `DTSTART` and the `RRULE`.
+    fn rule_start(&mut self) -> Option<DateTime<Utc>> {
+        let dtstart = self.dtstart?;
+        loop {
+            if let Some(start) = self.pending.pop_front() {
+                let repeat = self.repeat;
+                let ended = repeat.is_some_and(|repeat| {
+                    repeat.until.is_some_and(|until| start > until)
+                        || repeat.count.is_some_and(|count| self.emitted >= count)
+                });
+                if ended {
+                    self.dtstart = None;
+                    return None;
+                }
+                self.emitted += 1;
+                return Some(start);
+            }
+
+            let repeat = match self.repeat {
+                Some(repeat) => repeat,
+                // without a rule the event only occurs at DTSTART
+                _ => {
+                    if self.period > 0 {
+                        self.dtstart = None;
+                        return None;
+                    }
+                    self.period += 1;
+                    self.pending.push_back(dtstart);
+                    continue;
+                }
+            };
+            if self.period == 0 {
+                // DTSTART is always the first occurrence, even if the rule doesn't match it
+                self.pending.push_back(dtstart);
+            }
+            let interval = i64::from(repeat.interval.unwrap_or(1).max(1));
+            let fine = repeat.freq <= Frequency::Minutely;
+            let starts = match self
+                .period
+                .checked_mul(interval)
+                .filter(|_| !fine || self.period < MAX_FINE_PERIODS)
+                .and_then(|offset| repeat.period_starts(self.local(dtstart), offset))
+            {
+                Some(starts) => starts,
+                // the rule runs out of representable dates or is capped, only the pending starts
+                // are left
+                None => {
+                    self.repeat = None;
+                    self.period = self.period.max(1);
+                    continue;
+                }
+            };
+            self.period += 1;
+            let before = self.pending.len();
+            let tz = self.tz;
+            self.pending.extend(
+                starts
+                    .into_iter()
+                    .map(|start| match tz {
+                        Some(tz) => local_in(tz, start),
+                        None => DateTime::from_naive_utc_and_offset(start, Utc),
+                    })
+                    .filter(|start| *start > dtstart),
+            );
+            if self.pending.len() == before && self.period > 1 {
+                self.empty_periods += 1;
+                if self.empty_periods > MAX_EMPTY_PERIODS {
+                    self.dtstart = None;
+                    return None;
+                }
+            } else {
+                self.empty_periods = 0;
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Occurrences<'a> {
+    type Item = DateTime<Utc>;
+
+    fn next(&mut self) -> Option<DateTime<Utc>> {
+        loop {
+            if self.next_rule_start.is_none() {
+                self.next_rule_start = self.rule_start();
+            }
+            let start = match (self.next_rule_start, self.rdates.last().copied()) {
+                (Some(rule), Some(rdate)) if rdate <= rule => {
+                    self.rdates.pop();
+                    if rdate == rule {
+                        self.next_rule_start = None;
+                    }
+                    rdate
+                }
+                (Some(_), _) => self.next_rule_start.take()?,
+                (None, Some(_)) => self.rdates.pop()?,
+                (None, None) => return None,
+            };
+            if !self.exdates.contains(&start) {
+                return Some(start);
+            }
+        }
+    }
+}
+
+impl Event {
+    ///Check if the events is all day.
+    ///
+    /// Events starting on a DATE are all day, for others it is guessed from their length.
+    pub fn is_all_day(&self) -> Option<bool> {
+        if self.all_day && self.dtstart.is_some() {
+            return Some(true);
+        }
+        self.dtstart
+            .as_ref()
+            .zip(self.dtend.as_ref())
+            .map(|(start, end)| end.signed_duration_since(start).num_hours() >= 24)
+    }
+
+    /// The day the event starts on.
+    pub fn start_date(&self) -> Option<NaiveDate> {
+        self.dtstart.map(|start| start.date_naive())
+    }
+
+    /// The last day of the event.
+    ///
+    /// The exclusive `DTEND` of an all-day event is the day after its last day, one without a
+    /// `DTEND` lasts a single day.
+    ///
+    /// ```
+    /// use chrono::{Duration, NaiveDate};
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      DTSTART;VALUE=DATE:20190522\r\n\
+    ///      DTEND;VALUE=DATE:20190523\r\n\
+    ///      DTSTAMP:20190501T090000Z\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DESCRIPTION:Holiday\r\n\
+    ///      LOCATION:Home\r\n\
+    ///      SEQUENCE:0\r\n\
+    ///      STATUS:CONFIRMED\r\n\
+    ///      SUMMARY:Holiday\r\n\
+    ///      TRANSP:TRANSPARENT\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let event = &ical.events[0];
+    /// let day = NaiveDate::from_ymd_opt(2019, 5, 22);
+    /// assert_eq!(event.is_all_day(), Some(true));
+    /// assert_eq!(event.start_date(), day);
+    /// assert_eq!(event.end_date(), day);
+    /// assert_eq!(event.dtend.unwrap() - event.dtstart.unwrap(), Duration::days(1));
+    ///
+    /// let out = ical.to_ics_string();
+    /// assert!(out.contains("DTSTART;VALUE=DATE:20190522\r\n"));
+    /// assert!(out.contains("DTEND;VALUE=DATE:20190523\r\n"));
+    /// ```
+    pub fn end_date(&self) -> Option<NaiveDate> {
+        match self.dtend {
+            Some(end) if self.all_day => Some((end - Duration::days(1)).date_naive()),
+            Some(end) => Some(end.date_naive()),
+            None => self.start_date(),
+        }
+    }
+
+    /// The last day of an all-day event, i.e. the day before its exclusive `DTEND`. `None` for
+    /// events with a time of day.
+    ///
+    /// ```
+    /// use chrono::NaiveDate;
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:day@example.com\r\n\
+    ///      DTSTART;VALUE=DATE:20190505\r\n\
+    ///      DTEND;VALUE=DATE:20190506\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:conference@example.com\r\n\
+    ///      DTSTART;VALUE=DATE:20190505\r\n\
+    ///      DTEND;VALUE=DATE:20190508\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:sync@example.com\r\n\
+    ///      DTSTART:20190505T090000Z\r\n\
+    ///      DTEND:20190505T100000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let day = |day| NaiveDate::from_ymd_opt(2019, 5, day);
+    /// assert_eq!(ical.events[0].inclusive_end_date(), day(5));
+    /// assert_eq!(ical.events[1].inclusive_end_date(), day(7));
+    /// assert_eq!(ical.events[2].inclusive_end_date(), None);
+    /// ```
+    pub fn inclusive_end_date(&self) -> Option<NaiveDate> {
+        if self.all_day {
+            self.end_date()
+        } else {
+            None
+        }
+    }
+
+    /// Attach a preferred display zone, leaving the event's times untouched.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      X-WR-TIMEZONE:America/New_York\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTART;TZID=Europe/Berlin:20190522T090000\r\n\
+    ///      SUMMARY:Sync\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:2@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      SUMMARY:Lunch\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(ical.events[0].display_tz(), Some("Europe/Berlin"));
+    /// assert_eq!(ical.events[1].display_tz(), Some("America/New_York"));
+    ///
+    /// let event = ical.events[1].clone().with_timezone_display("Asia/Tokyo");
+    /// assert_eq!(event.display_tz(), Some("Asia/Tokyo"));
+    /// assert_eq!(event.dtstart, ical.events[1].dtstart);
+    /// assert_eq!(event.one_line_summary(), "2019-05-22 09:00 (Asia/Tokyo) Lunch");
+    /// ```
+    pub fn with_timezone_display(mut self, tzid: impl Into<String>) -> Event {
+        self.display_tz = Some(tzid.into());
+        self
+    }
+
+    /// Make this an all-day event on `date`, exported with `VALUE=DATE` start and end. Use
+    /// [`Event::ends_on`] for events spanning several days.
+    ///
+    /// ```
+    /// use chrono::{NaiveDate, TimeZone, Utc};
+    /// use web_ical::{Calendar, Event};
+    ///
+    /// let day = |day| NaiveDate::from_ymd_opt(2019, 5, day).unwrap();
+    /// let mut blocked = Event::empty().starts_on(day(22));
+    /// blocked.uid = Some("blocked@example.com".to_string());
+    /// blocked.summary = Some("Not available".to_string());
+    /// assert!(blocked.all_day);
+    /// assert_eq!(blocked.dtend, Some(Utc.with_ymd_and_hms(2019, 5, 23, 0, 0, 0).unwrap()));
+    /// assert_eq!(blocked.inclusive_end_date(), Some(day(22)));
+    ///
+    /// let mut ical = Calendar::create("-//Test//EN", "2.0", "GREGORIAN", "PUBLISH", "", "UTC");
+    /// ical.add_event(blocked.clone());
+    /// ical.add_event(blocked.ends_on(day(24)));
+    /// let out = ical.to_ics_string();
+    /// assert!(out.contains("DTSTART;VALUE=DATE:20190522\r\nDTEND;VALUE=DATE:20190523\r\n"));
+    /// assert!(out.contains("DTSTART;VALUE=DATE:20190522\r\nDTEND;VALUE=DATE:20190525\r\n"));
+    /// ```
+    pub fn starts_on(mut self, date: NaiveDate) -> Event {
+        self.dtstart = Some(day_start(date));
+        self.dtend = date.succ_opt().map(day_start);
+        self.all_day = true;
+        self
+    }
+
+    /// Make this an all-day event lasting up to and including `last`. As RFC 5545 defines it,
+    /// `dtend` becomes the exclusive day after.
+    pub fn ends_on(mut self, last: NaiveDate) -> Event {
+        self.dtend = last.succ_opt().map(day_start);
+        self.all_day = true;
+        self
+    }
+
+    /// Repeat the event according to `repeat`, which is exported as its `RRULE`. This sets any rule,
+    /// [`Event::repeat_daily`] and [`Event::repeat_weekly_until`] cover the common cases.
+    ///
+    /// ```
+    /// use chrono::{NaiveDate, TimeZone, Utc};
+    /// use web_ical::{Calendar, Event, Frequency, Repeat};
+    ///
+    /// let mut repeat = Repeat::new(Frequency::Monthly);
+    /// repeat.by_day = vec!["-1FR".to_string()];
+    /// let mut event = Event::empty().with_repeat(repeat);
+    /// event.set_dt_start("20190531T170000Z").unwrap();
+    /// assert_eq!(
+    ///     event.first_n_occurrences(2)[1],
+    ///     Utc.with_ymd_and_hms(2019, 6, 28, 17, 0, 0).unwrap()
+    /// );
+    ///
+    /// let day = |day| NaiveDate::from_ymd_opt(2019, 5, day).unwrap();
+    /// let mut standup = Event::empty().repeat_daily(5);
+    /// standup.uid = Some("standup@example.com".to_string());
+    /// standup.set_dt_start("20190520T090000Z").unwrap();
+    /// let days: Vec<_> = standup
+    ///     .first_n_occurrences(10)
+    ///     .iter()
+    ///     .map(|start| start.date_naive())
+    ///     .collect();
+    /// assert_eq!(days, [day(20), day(21), day(22), day(23), day(24)]);
+    ///
+    /// let mut ical = Calendar::create("-//Test//EN", "2.0", "GREGORIAN", "PUBLISH", "", "UTC");
+    /// ical.add_event(standup);
+    /// ical.add_event(Event::empty().starts_on(day(20)).repeat_weekly_until(day(31)));
+    /// let out = ical.to_ics_string();
+    /// assert!(out.contains("RRULE:FREQ=DAILY;COUNT=5\r\n"));
+    /// // the all-day event ends its rule on a DATE like its DTSTART
+    /// assert!(out.contains("DTSTART;VALUE=DATE:20190520\r\n"));
+    /// assert!(out.contains("RRULE:FREQ=WEEKLY;UNTIL=20190531\r\n"));
+    /// assert_eq!(ical.events[1].first_n_occurrences(10).len(), 2);
+    /// ```
+    pub fn with_repeat(mut self, repeat: Repeat) -> Event {
+        self.repeat = Some(repeat);
+        self
+    }
+
+    /// Repeat the event on `count` consecutive days, see [`Event::with_repeat`].
+    pub fn repeat_daily(self, count: u32) -> Event {
+        let mut repeat = Repeat::new(Frequency::Daily);
+        repeat.count = Some(count);
+        self.with_repeat(repeat)
+    }
+
+    /// Repeat the event every week up to and including the day `last`, see
+    /// [`Event::with_repeat`].
+    pub fn repeat_weekly_until(self, last: NaiveDate) -> Event {
+        let mut repeat = Repeat::new(Frequency::Weekly);
+        repeat.until = last
+            .succ_opt()
+            .map(|next| day_start(next) - Duration::seconds(1));
+        self.with_repeat(repeat)
+    }
+
+    /// The TZID the event should preferably be displayed in.
+    pub fn display_tz(&self) -> Option<&str> {
+        self.display_tz.as_deref()
+    }
+
+    /// A short single line description of the event made up of its start, display zone and
+    /// summary, e.g. `2019-05-22 09:00 (Europe/Berlin) Sync`.
+    pub fn one_line_summary(&self) -> String {
+        let mut line = String::new();
+        if let Some(start) = self.dtstart.as_ref() {
+            line.push_str(&start.format("%Y-%m-%d %H:%M ").to_string());
+        }
+        if let Some(tz) = self.display_tz.as_ref() {
+            line.push_str(&format!("({}) ", tz));
+        }
+        line.push_str(self.summary.as_deref().unwrap_or_default());
+        line.trim_end().to_string()
+    }
+
+    /// Summary with RFC 5545 TEXT escaping applied, ready to be embedded into an ICS line.
+    ///
+    /// ```
+    /// use web_ical::Event;
+    ///
+    /// let mut event = Event::empty();
+    /// event.summary = Some("Lunch, then\nreview; maybe".to_string());
+    /// assert_eq!(event.escaped_summary(), "Lunch\\, then\\nreview\\; maybe");
+    /// ```
+    pub fn escaped_summary(&self) -> String {
+        escape_text(self.summary.as_deref().unwrap_or_default())
+    }
+
+    /// Description with RFC 5545 TEXT escaping applied.
+    pub fn escaped_description(&self) -> String {
+        escape_text(self.description.as_deref().unwrap_or_default())
+    }
+
+    /// Location with RFC 5545 TEXT escaping applied.
+    pub fn escaped_location(&self) -> String {
+        escape_text(self.location.as_deref().unwrap_or_default())
+    }
+
+    /// Postal address given in the `X-ADDRESS` parameter of `LOCATION`.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTEND:20190522T100000Z\r\n\
+    ///      DTSTAMP:20190501T090000Z\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      CREATED:20190501T090000Z\r\n\
+    ///      DESCRIPTION:Visit\r\n\
+    ///      LAST-MODIFIED:20190501T090000Z\r\n\
+    ///      LOCATION;X-ADDRESS=\"1 Apple Park Way, Cupertino, CA\";X-TITLE=Apple:Apple Park\r\n\
+    ///      SEQUENCE:0\r\n\
+    ///      STATUS:CONFIRMED\r\n\
+    ///      SUMMARY:Visit\r\n\
+    ///      TRANSP:OPAQUE\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let event = &ical.events[0];
+    /// assert_eq!(event.location.as_deref(), Some("Apple Park"));
+    /// assert_eq!(event.location_address(), Some("1 Apple Park Way, Cupertino, CA"));
+    ///
+    /// let mut out = Vec::new();
+    /// ical.export_to(&mut out).unwrap();
+    /// let ical = Calendar::new_from_data(&String::from_utf8(out).unwrap()).unwrap();
+    /// assert_eq!(ical.events[0].location_params, event.location_params);
+    /// ```
+    pub fn location_address(&self) -> Option<&str> {
+        param(&self.location_params, "X-ADDRESS")
+    }
+
+    /// The participation status of the attendee with the given email, compared case-insensitively.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      ATTENDEE;PARTSTAT=ACCEPTED:MAILTO:Jane.Doe@Example.com\r\n\
+    ///      ATTENDEE;PARTSTAT=DECLINED:mailto:bob@example.com\r\n\
+    ///      ATTENDEE:urn:uuid:0b6a2cd6-1a1c-4b12-9e27-3f3e4a6d2a11\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let event = &ical.events[0];
+    /// assert_eq!(event.attendees[0].email().as_deref(), Some("jane.doe@example.com"));
+    /// assert_eq!(event.attendees[2].email(), None);
+    /// assert_eq!(event.attendee_partstat("jane.doe@example.com"), Some("ACCEPTED"));
+    /// assert_eq!(event.attendee_partstat("mailto:BOB@example.com"), Some("DECLINED"));
+    /// assert_eq!(event.attendee_partstat("eve@example.com"), None);
+    /// ```
+    pub fn attendee_partstat(&self, email: &str) -> Option<&str> {
+        let email = normalize_email(email)?;
+        self.attendees
+            .iter()
+            .find(|attendee| attendee.email().as_ref() == Some(&email))
+            .and_then(|attendee| attendee.partstat.as_deref())
+    }
+
+    /// The RGB value of the event's [`color`](Event::color), `None` if it is unset or not a CSS3
+    /// color name.
+    pub fn color_rgb(&self) -> Option<(u8, u8, u8)> {
+        css_color(self.color.as_deref()?)
+    }
+
+    /// The starts of the event's occurrences in order, expanding its `RRULE` lazily.
+    ///
+    /// `DTSTART` is always the first occurrence, `RDATE`s are added and `EXDATE`s left out.
+    /// `BYWEEKNO` is not supported and ignored, and rules whose frequency is not known yield only
+    /// `DTSTART`. `SECONDLY` and `MINUTELY` rules end after 100 000 periods, and any rule ends
+    /// once its occurrences are out of the range of dates.
+    ///
+    /// Rules are expanded in the local time of [`Event::display_tz`], so the occurrences keep
+    /// their time of day across DST changes.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTART:20190506T090000Z\r\n\
+    ///      RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=5\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:2@example.com\r\n\
+    ///      DTSTART:20190131T180000Z\r\n\
+    ///      RRULE:FREQ=MONTHLY;BYMONTHDAY=-1\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let days: Vec<u32> = ical.events[0].occurrences().map(|start| start.day()).collect();
+    /// assert_eq!(days, [6, 8, 13, 15, 20]);
+    ///
+    /// let last_days: Vec<_> = ical.events[1].occurrences().take(3).collect();
+    /// assert_eq!(
+    ///     last_days,
+    ///     [
+    ///         Utc.with_ymd_and_hms(2019, 1, 31, 18, 0, 0).unwrap(),
+    ///         Utc.with_ymd_and_hms(2019, 2, 28, 18, 0, 0).unwrap(),
+    ///         Utc.with_ymd_and_hms(2019, 3, 31, 18, 0, 0).unwrap(),
+    ///     ]
+    /// );
+    /// # use chrono::Datelike;
+    /// ```
+    pub fn occurrences(&self) -> Occurrences<'_> {
+        let mut rdates = self.rdates.clone();
+        rdates.sort_by(|a, b| b.cmp(a));
+        rdates.dedup();
+        // DATEs and floating times are stored as if they were UTC
+        let tz = self
+            .display_tz
+            .as_deref()
+            .filter(|_| !self.all_day && !self.floating)
+            .and_then(|tzid| tzid.trim_start_matches('/').parse().ok());
+        Occurrences {
+            dtstart: self.dtstart,
+            tz,
+            repeat: self.repeat.as_ref(),
+            period: 0,
+            pending: Default::default(),
+            emitted: 0,
+            empty_periods: 0,
+            rdates,
+            exdates: &self.exdates,
+            next_rule_start: None,
+        }
+    }
+
+    /// The starts of the first `n` occurrences, e.g. to preview a series.
+    ///
+    /// Like [`Event::occurrences`] this returns fewer starts if the rule ends earlier, or stops
+    /// matching dates at all, so it is safe to call with unbounded rules.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTART:20190131T090000Z\r\n\
+    ///      RRULE:FREQ=MONTHLY\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let at = |month| Utc.with_ymd_and_hms(2019, month, 31, 9, 0, 0).unwrap();
+    /// assert_eq!(
+    ///     ical.events[0].first_n_occurrences(5),
+    ///     [at(1), at(3), at(5), at(7), at(8)]
+    /// );
+    /// assert!(ical.events[0].first_n_occurrences(0).is_empty());
+    /// ```
+    pub fn first_n_occurrences(&self, n: usize) -> Vec<DateTime<Utc>> {
+        self.occurrences().take(n).collect()
+    }
+
+    /// How long the event lasts, from `DTSTART` to `DTEND` or as given in `DURATION`. `None` if
+    /// neither end is known.
+    ///
+    /// ```
+    /// use chrono::Duration;
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTEND:20190522T103000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:2@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DURATION:PT45M\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:3@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(ical.events[0].duration(), Some(Duration::minutes(90)));
+    /// assert_eq!(ical.events[1].duration(), Some(Duration::minutes(45)));
+    /// assert_eq!(ical.events[2].duration(), None);
+    /// ```
+    pub fn duration(&self) -> Option<Duration> {
+        match (self.dtstart, self.dtend) {
+            (Some(start), Some(end)) => Some(end - start),
+            (_, None) => self.duration,
+            (None, Some(_)) => None,
+        }
+    }
+
+    /// How long an occurrence of the event lasts, from `DTEND` or `DURATION`, zero if it has
+    /// neither.
+    fn length(&self) -> Duration {
+        match (self.dtstart, self.dtend, self.duration) {
+            (Some(start), Some(end), _) if end > start => end - start,
+            (_, None, Some(duration)) if duration > Duration::zero() => duration,
+            (_, None, None) if self.all_day => Duration::days(1),
+            _ => Duration::zero(),
+        }
+    }
+
+    /// The time from `now` until the start of the next occurrence, negative if an occurrence is
+    /// ongoing or all of them are over. `None` if the event has no start.
+    ///
+    /// ```
+    /// use chrono::{Duration, TimeZone, Utc};
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTEND:20190522T100000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:2@example.com\r\n\
+    ///      DTSTART:20190506T090000Z\r\n\
+    ///      DTEND:20190506T100000Z\r\n\
+    ///      RRULE:FREQ=WEEKLY\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let now = Utc.with_ymd_and_hms(2019, 5, 20, 9, 30, 0).unwrap();
+    ///
+    /// let single = &ical.events[0];
+    /// assert_eq!(single.time_until(now), Some(Duration::hours(47) + Duration::minutes(30)));
+    /// let later = Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap();
+    /// assert!(single.time_until(later).unwrap() < Duration::zero());
+    ///
+    /// let weekly = &ical.events[1];
+    /// assert_eq!(weekly.time_until(now), Some(Duration::minutes(-30)));
+    /// assert_eq!(weekly.time_until(later), Some(Duration::days(2) + Duration::hours(9)));
+    /// ```
+    pub fn time_until(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let length = self.length();
+        let mut last = None;
+        for start in self.occurrences() {
+            if start >= now || end_after(start, length) > now {
+                return Some(start - now);
+            }
+            last = Some(start);
+        }
+        last.map(|start| start - now)
+    }
+
+    /// Whether the event blocks time, i.e. it is not marked `TRANSP:TRANSPARENT`.
+    ///
+    /// ```
+    /// use web_ical::{Calendar, TimeTransparency};
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      TRANSP:TRANSPARENT\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:2@example.com\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(ical.events[0].transp, TimeTransparency::Transparent);
+    /// assert!(!ical.events[0].blocks_time());
+    /// assert_eq!(ical.events[1].transp, TimeTransparency::Opaque);
+    /// assert!(ical.events[1].blocks_time());
+    /// ```
+    pub fn blocks_time(&self) -> bool {
+        self.transp == TimeTransparency::Opaque
+    }
+
+    /// Whether the time of this event and `other` overlaps, from their starts up to but
+    /// excluding their ends, so that back to back events don't overlap.
+    ///
+    /// An all-day event lasts from midnight to midnight (UTC) and an event with neither an end
+    /// nor a duration is a point in time. Only the first occurrences are compared and events
+    /// without a start overlap nothing.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:standup@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTEND:20190522T100000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:review@example.com\r\n\
+    ///      DTSTART:20190522T093000Z\r\n\
+    ///      DURATION:PT1H\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:lunch@example.com\r\n\
+    ///      DTSTART:20190522T100000Z\r\n\
+    ///      DTEND:20190522T110000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:offsite@example.com\r\n\
+    ///      DTSTART;VALUE=DATE:20190523\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:deadline@example.com\r\n\
+    ///      DTSTART:20190523T170000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let [standup, review, lunch, offsite, deadline] = &ical.events[..] else {
+    ///     panic!("expected five events")
+    /// };
+    /// // overlapping
+    /// assert!(standup.overlaps(review) && review.overlaps(standup));
+    /// assert!(review.overlaps(lunch));
+    /// // touching
+    /// assert!(!standup.overlaps(lunch));
+    /// // disjoint
+    /// assert!(!lunch.overlaps(offsite));
+    /// // a point in time within an all-day event
+    /// assert!(deadline.overlaps(offsite) && offsite.overlaps(deadline));
+    /// assert!(deadline.overlaps(deadline));
+    /// ```
+    pub fn overlaps(&self, other: &Event) -> bool {
+        let (start, other_start) = match (self.dtstart, other.dtstart) {
+            (Some(start), Some(other_start)) => (start, other_start),
+            _ => return false,
+        };
+        let end = end_after(start, self.length());
+        let other_end = end_after(other_start, other.length());
+        // half-open intervals overlap if one starts within the other, points being within
+        // the intervals they start
+        let starts_within = |start, from, to| from <= start && (start < to || start == from);
+        starts_within(start, other_start, other_end) || starts_within(other_start, start, end)
+    }
+
+    /// Whether the `PRIORITY` is one of the high ones from 1 to 4.
+    pub fn is_high_priority(&self) -> bool {
+        matches!(self.priority, Some(1..=4))
+    }
+
+    /// Whether the `PRIORITY` is one of the low ones from 6 to 9.
+    pub fn is_low_priority(&self) -> bool {
+        matches!(self.priority, Some(6..=9))
+    }
+
+    /// The `URL` parsed as an absolute URL, `None` if there is none or it is malformed.
+    ///
+    /// Any scheme is accepted, feeds also put `mailto:` or `data:` URIs there.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      URL:https://example.com/events?id=42&lang=de%2DCH;view=full\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:2@example.com\r\n\
+    ///      URL:mailto:host@example.com\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:3@example.com\r\n\
+    ///      URL:/events/42\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let url = ical.events[0].url_parsed().unwrap();
+    /// assert_eq!(url.host_str(), Some("example.com"));
+    /// let query: Vec<_> = url.query_pairs().collect();
+    /// assert_eq!(query[1], ("lang".into(), "de-CH;view=full".into()));
+    /// assert!(ical
+    ///     .to_ics_string()
+    ///     .contains("URL:https://example.com/events?id=42&lang=de%2DCH;view=full\r\n"));
+    ///
+    /// assert_eq!(ical.events[1].url_parsed().unwrap().scheme(), "mailto");
+    /// assert_eq!(ical.events[2].url_parsed(), None);
+    /// ```
+    pub fn url_parsed(&self) -> Option<reqwest::Url> {
+        reqwest::Url::parse(self.url.as_deref()?).ok()
+    }
+
+    /// Whether the event is over, going on or yet to come at `now`, looking at the next
+    /// occurrence of recurring events.
+    ///
+    /// An event without a start hasn't happened yet and counts as [`TemporalState::Future`].
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use web_ical::{Calendar, TemporalState};
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTEND:20190522T100000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:2@example.com\r\n\
+    ///      DTSTART:20190506T090000Z\r\n\
+    ///      DTEND:20190506T100000Z\r\n\
+    ///      RRULE:FREQ=WEEKLY;COUNT=4\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let single = &ical.events[0];
+    /// let at = |day, hour, minute| Utc.with_ymd_and_hms(2019, 5, day, hour, minute, 0).unwrap();
+    ///
+    /// assert_eq!(single.temporal_state(at(21, 9, 0)), TemporalState::Future);
+    /// assert_eq!(single.temporal_state(at(22, 9, 0)), TemporalState::Ongoing);
+    /// assert_eq!(single.temporal_state(at(22, 10, 0)), TemporalState::Past);
+    ///
+    /// let weekly = &ical.events[1];
+    /// assert_eq!(weekly.temporal_state(at(20, 9, 30)), TemporalState::Ongoing);
+    /// assert_eq!(weekly.temporal_state(at(20, 11, 0)), TemporalState::Future);
+    /// assert_eq!(weekly.temporal_state(at(27, 11, 0)), TemporalState::Past);
+    /// ```
+    pub fn temporal_state(&self, now: DateTime<Utc>) -> TemporalState {
+        match self.time_until(now) {
+            Some(until) if until <= Duration::zero() => {
+                if until + self.length() > Duration::zero() || until == Duration::zero() {
+                    TemporalState::Ongoing
+                } else {
+                    TemporalState::Past
+                }
+            }
+            _ => TemporalState::Future,
+        }
+    }
+
+    pub fn empty() -> Event {
+        Event {
+            dtstart: None,
+            dtend: None,
+            duration: None,
+            dtstamp: None,
+            uid: None,
+            created: None,
+            description: None,
+            description_language: None,
+            description_html: None,
+            description_altrep: None,
+            last_modified: None,
+            location: None,
+            organizer: None,
+            sequence: None,
+            status: None,
+            summary: None,
+            summary_language: None,
+            transp: TimeTransparency::default(),
+            repeat: None,
+            rdates: vec![],
+            exdates: vec![],
+            class: None,
+            geo: None,
+            priority: None,
+            percent_complete: None,
+            completed: None,
+            recur_id: None,
+            url: None,
+            color: None,
+            categories: vec![],
+            contact: vec![],
+            comment: vec![],
+            resources: vec![],
+            attendees: vec![],
+            attachments: vec![],
+            location_params: vec![],
+            floating: false,
+            all_day: false,
+            display_tz: None,
+            alarms: vec![],
+            extra: vec![],
+            property_order: vec![],
+        }
+    }
+}
+
+/// The `STATUS` of an event, values are matched case-insensitively.
+///
+/// ```
+/// use web_ical::{Calendar, EventStatus};
+///
+/// let data = "BEGIN:VCALENDAR\r\n\
+///             PRODID:-//Test//EN\r\n\
+///             VERSION:2.0\r\n\
+///             BEGIN:VEVENT\r\n\
+///             DTSTART:20190522T090000Z\r\n\
+///             DTEND:20190522T100000Z\r\n\
+///             DTSTAMP:20190501T090000Z\r\n\
+///             UID:1@example.com\r\n\
+///             DESCRIPTION:Sync\r\n\
+///             LOCATION:Office\r\n\
+///             SEQUENCE:0\r\n\
+///             STATUS:Cancelled\r\n\
+///             SUMMARY:Sync\r\n\
+///             TRANSP:OPAQUE\r\n\
+///             END:VEVENT\r\n\
+///             END:VCALENDAR\r\n";
+/// let ical = Calendar::new_from_data(data).unwrap();
+/// assert_eq!(ical.events[0].status, Some(EventStatus::Cancelled));
+/// assert!(ical.to_ics_string().contains("STATUS:CANCELLED\r\n"));
+///
+/// let ical = Calendar::new_from_data(&data.replace("Cancelled", "X-POSTPONED")).unwrap();
+/// assert_eq!(
+///     ical.events[0].status,
+///     Some(EventStatus::Other("X-POSTPONED".to_string()))
+/// );
+/// assert!(ical.to_ics_string().contains("STATUS:X-POSTPONED\r\n"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventStatus {
+    Tentative,
+    Confirmed,
+    Cancelled,
+    /// A value RFC 5545 doesn't define for events, kept as it is.
+    Other(String),
+}
+
+impl From<&str> for EventStatus {
+    fn from(value: &str) -> EventStatus {
+        match value.to_ascii_uppercase().as_str() {
+            "TENTATIVE" => EventStatus::Tentative,
+            "CONFIRMED" => EventStatus::Confirmed,
+            "CANCELLED" => EventStatus::Cancelled,
+            _ => EventStatus::Other(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for EventStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EventStatus::Tentative => "TENTATIVE",
+            EventStatus::Confirmed => "CONFIRMED",
+            EventStatus::Cancelled => "CANCELLED",
+            EventStatus::Other(value) => value,
+        })
+    }
+}
+
+/// The access `CLASS` of an event, values are matched case-insensitively.
+///
+/// ```
+/// use web_ical::{Calendar, Classification};
+///
+/// let data = "BEGIN:VCALENDAR\r\n\
+///             PRODID:-//Test//EN\r\n\
+///             VERSION:2.0\r\n\
+///             BEGIN:VEVENT\r\n\
+///             UID:1@example.com\r\n\
+///             CLASS:private\r\n\
+///             END:VEVENT\r\n\
+///             END:VCALENDAR\r\n";
+/// let ical = Calendar::new_from_data(data).unwrap();
+/// assert_eq!(ical.events[0].class, Some(Classification::Private));
+/// assert_eq!(Classification::Private.to_string(), "PRIVATE");
+///
+/// let ical = Calendar::new_from_data(&data.replace("private", "X-SECRET")).unwrap();
+/// assert_eq!(
+///     ical.events[0].class,
+///     Some(Classification::Other("X-SECRET".to_string()))
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Classification {
+    Public,
+    Private,
+    Confidential,
+    /// An experimental or IANA registered value, kept as it is.
+    Other(String),
+}
+
+impl From<&str> for Classification {
+    fn from(value: &str) -> Classification {
+        match value.to_ascii_uppercase().as_str() {
+            "PUBLIC" => Classification::Public,
+            "PRIVATE" => Classification::Private,
+            "CONFIDENTIAL" => Classification::Confidential,
+            _ => Classification::Other(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Classification::Public => "PUBLIC",
+            Classification::Private => "PRIVATE",
+            Classification::Confidential => "CONFIDENTIAL",
+            Classification::Other(value) => value,
+        })
+    }
+}
+
+/// Whether an event blocks time on free/busy lookups as given by `TRANSP`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimeTransparency {
+    #[default]
+    Opaque,
+    Transparent,
+}
+
+impl From<&str> for TimeTransparency {
+    fn from(value: &str) -> TimeTransparency {
+        if value.eq_ignore_ascii_case("TRANSPARENT") {
+            TimeTransparency::Transparent
+        } else {
+            TimeTransparency::Opaque
+        }
+    }
+}
+
+impl fmt::Display for TimeTransparency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TimeTransparency::Opaque => "OPAQUE",
+            TimeTransparency::Transparent => "TRANSPARENT",
+        })
+    }
+}
+
+/// The iTIP (RFC 5546) `METHOD` of a calendar, values are matched case-insensitively.
+///
+/// ```
+/// use web_ical::{Calendar, Method};
+///
+/// let methods = [
+///     ("PUBLISH", Method::Publish),
+///     ("REQUEST", Method::Request),
+///     ("REPLY", Method::Reply),
+///     ("ADD", Method::Add),
+///     ("CANCEL", Method::Cancel),
+///     ("REFRESH", Method::Refresh),
+///     ("COUNTER", Method::Counter),
+///     ("DECLINECOUNTER", Method::DeclineCounter),
+///     ("X-MOVE", Method::Other("X-MOVE".to_string())),
+/// ];
+/// for (value, method) in methods {
+///     let data = format!(
+///         "BEGIN:VCALENDAR\r\nPRODID:-//Test//EN\r\nVERSION:2.0\r\nMETHOD:{}\r\nEND:VCALENDAR\r\n",
+///         value
+///     );
+///     let ical = Calendar::new_from_data(&data).unwrap();
+///     assert_eq!(ical.method.as_ref(), Some(&method));
+///     assert_eq!(ical.is_invitation(), method == Method::Request);
+///     assert_eq!(method.to_string(), value);
+/// }
+/// assert_eq!(Method::from("declinecounter"), Method::DeclineCounter);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Method {
+    Publish,
+    Request,
+    Reply,
+    Add,
+    Cancel,
+    Refresh,
+    Counter,
+    DeclineCounter,
+    /// An experimental or IANA registered value, kept as it is.
+    Other(String),
+}
+
+impl From<&str> for Method {
+    fn from(value: &str) -> Method {
+        match value.to_ascii_uppercase().as_str() {
+            "PUBLISH" => Method::Publish,
+            "REQUEST" => Method::Request,
+            "REPLY" => Method::Reply,
+            "ADD" => Method::Add,
+            "CANCEL" => Method::Cancel,
+            "REFRESH" => Method::Refresh,
+            "COUNTER" => Method::Counter,
+            "DECLINECOUNTER" => Method::DeclineCounter,
+            _ => Method::Other(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Method::Publish => "PUBLISH",
+            Method::Request => "REQUEST",
+            Method::Reply => "REPLY",
+            Method::Add => "ADD",
+            Method::Cancel => "CANCEL",
+            Method::Refresh => "REFRESH",
+            Method::Counter => "COUNTER",
+            Method::DeclineCounter => "DECLINECOUNTER",
+            Method::Other(value) => value,
+        })
+    }
+}
+
+/// Where an event lies in time relative to a given instant, see [`Event::temporal_state`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TemporalState {
+    Past,
+    Ongoing,
+    Future,
+}
+
+/// When an alarm goes off.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Trigger {
+    /// Relative to the start of the event, negative values lie before it.
+    Relative(#[cfg_attr(feature = "serde", serde(with = "serde_duration"))] Duration),
+    Absolute(DateTime<Utc>),
+}
+
+/// A reminder as found in a `VALARM` component nested in an event.
+///
+/// ```
+/// use chrono::Duration;
+/// use web_ical::{Calendar, Trigger};
+///
+/// let ical = Calendar::new_from_data(
+///     "BEGIN:VCALENDAR\r\n\
+///      PRODID:-//Test//EN\r\n\
+///      VERSION:2.0\r\n\
+///      BEGIN:VEVENT\r\n\
+///      DTSTART:20190522T090000Z\r\n\
+///      DTEND:20190522T100000Z\r\n\
+///      DTSTAMP:20190501T090000Z\r\n\
+///      UID:1@example.com\r\n\
+///      CREATED:20190501T090000Z\r\n\
+///      DESCRIPTION:Planning\r\n\
+///      LAST-MODIFIED:20190501T090000Z\r\n\
+///      LOCATION:Office\r\n\
+///      SEQUENCE:0\r\n\
+///      STATUS:CONFIRMED\r\n\
+///      SUMMARY:Planning\r\n\
+///      TRANSP:OPAQUE\r\n\
+///      BEGIN:VALARM\r\n\
+///      ACTION:DISPLAY\r\n\
+///      TRIGGER:-PT15M\r\n\
+///      DESCRIPTION:Planning starts soon\r\n\
+///      END:VALARM\r\n\
+///      END:VEVENT\r\n\
+///      END:VCALENDAR\r\n",
+/// )
+/// .unwrap();
+/// let alarm = &ical.events[0].alarms[0];
+/// assert_eq!(alarm.action, "DISPLAY");
+/// assert_eq!(alarm.trigger, Some(Trigger::Relative(-Duration::minutes(15))));
+/// assert_eq!(alarm.description.as_deref(), Some("Planning starts soon"));
+///
+/// let mut out = Vec::new();
+/// ical.export_to(&mut out).unwrap();
+/// let out = String::from_utf8(out).unwrap();
+/// assert!(out.contains("BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT15M\r\n"));
+///
+/// let ical = Calendar::new_from_data(&out).unwrap();
+/// assert_eq!(ical.events[0].alarms.len(), 1);
+/// assert_eq!(
+///     ical.events[0].alarms[0].trigger,
+///     Some(Trigger::Relative(-Duration::minutes(15)))
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Alarm {
+    pub action: String,
+    pub trigger: Option<Trigger>,
+    pub description: Option<String>,
+}
+
+impl Alarm {
+    fn write_component<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "BEGIN:VALARM\r\n")?;
+        write_property(writer, &format!("ACTION:{}", self.action))?;
+        match self.trigger.as_ref() {
+            Some(Trigger::Relative(duration)) => {
+                write_property(writer, &format!("TRIGGER:{}", format_duration(*duration)))?;
+            }
+            Some(Trigger::Absolute(time)) => {
+                write_property(
+                    writer,
+                    &format!("TRIGGER;VALUE=DATE-TIME:{}", time.format("%Y%m%dT%H%M%SZ")),
+                )?;
+            }
+            None => (),
+        }
+        if let Some(description) = self.description.as_ref() {
+            write_property(writer, &format!("DESCRIPTION:{}", escape_text(description)))?;
+        }
+        write!(writer, "END:VALARM\r\n")
+    }
+}
+
+/// A journal entry as found in a `VJOURNAL` component.
+///
+/// ```
+/// use web_ical::Calendar;
+///
+/// let ical = Calendar::new_from_data(
+///     "BEGIN:VCALENDAR\r\n\
+///      PRODID:-//Test//EN\r\n\
+///      VERSION:2.0\r\n\
+///      BEGIN:VJOURNAL\r\n\
+///      UID:journal-1@example.com\r\n\
+///      DTSTAMP:20190522T180000Z\r\n\
+///      DTSTART;VALUE=DATE:20190522\r\n\
+///      SUMMARY:Retrospective\r\n\
+///      DESCRIPTION:What went well:\\n- shipping on time\\n- fewer bugs\\nWhat to impr\r\n \
+///      ove:\\n- estimates\r\n\
+///      END:VJOURNAL\r\n\
+///      END:VCALENDAR\r\n",
+/// )
+/// .unwrap();
+/// let description = "What went well:\n- shipping on time\n- fewer bugs\nWhat to improve:\n- estimates";
+/// assert_eq!(ical.journals[0].description.as_deref(), Some(description));
+///
+/// assert!(ical.journals[0].all_day);
+///
+/// let mut out = Vec::new();
+/// ical.export_to(&mut out).unwrap();
+/// let out = String::from_utf8(out).unwrap();
+/// assert!(out.contains("DTSTART;VALUE=DATE:20190522\r\n"));
+/// let ical = Calendar::new_from_data(&out).unwrap();
+/// let journal = &ical.journals[0];
+/// assert_eq!(journal.uid.as_deref(), Some("journal-1@example.com"));
+/// assert_eq!(journal.summary.as_deref(), Some("Retrospective"));
+/// assert_eq!(journal.description.as_deref(), Some(description));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Journal {
+    pub uid: Option<String>,
+    pub dtstamp: Option<DateTime<Utc>>,
+    pub dtstart: Option<DateTime<Utc>>,
+    /// Whether `dtstart` was given as a DATE, as the entries of a diary usually are.
+    pub all_day: bool,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Journal {
+    fn write_component<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "BEGIN:VJOURNAL\r\n")?;
+        if let Some(uid) = self.uid.as_ref() {
+            write_property(writer, &format!("UID:{}", uid))?;
+        }
+        if let Some(dtstamp) = self.dtstamp.as_ref() {
+            write_property(
+                writer,
+                &format!("DTSTAMP:{}", dtstamp.format("%Y%m%dT%H%M%SZ")),
+            )?;
+        }
+        if let Some(dtstart) = self.dtstart.as_ref() {
+            let line = if self.all_day {
+                format!("DTSTART;VALUE=DATE:{}", dtstart.format("%Y%m%d"))
+            } else {
+                format!("DTSTART:{}", dtstart.format("%Y%m%dT%H%M%SZ"))
+            };
+            write_property(writer, &line)?;
+        }
+        if let Some(summary) = self.summary.as_ref() {
+            write_property(writer, &format!("SUMMARY:{}", escape_text(summary)))?;
+        }
+        if let Some(description) = self.description.as_ref() {
+            write_property(writer, &format!("DESCRIPTION:{}", escape_text(description)))?;
+        }
+        write!(writer, "END:VJOURNAL\r\n")
+    }
+}
+
+/// Free/busy information as found in a `VFREEBUSY` component.
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use web_ical::Calendar;
+///
+/// let ical = Calendar::new_from_data(
+///     "BEGIN:VCALENDAR\r\n\
+///      PRODID:-//Test//EN\r\n\
+///      VERSION:2.0\r\n\
+///      BEGIN:VFREEBUSY\r\n\
+///      ORGANIZER:mailto:jane@x.com\r\n\
+///      DTSTART:20200101T000000Z\r\n\
+///      DTEND:20200102T000000Z\r\n\
+///      FREEBUSY;FBTYPE=BUSY:20200101T080000Z/20200101T090000Z,20200101T130000Z/PT30M\r\n\
+///      END:VFREEBUSY\r\n\
+///      END:VCALENDAR\r\n",
+/// )
+/// .unwrap();
+/// let freebusy = &ical.freebusy[0];
+/// assert_eq!(freebusy.organizer.as_ref().unwrap().email, "jane@x.com");
+/// assert_eq!(
+///     freebusy.busy,
+///     [
+///         (
+///             Utc.with_ymd_and_hms(2020, 1, 1, 8, 0, 0).unwrap(),
+///             Utc.with_ymd_and_hms(2020, 1, 1, 9, 0, 0).unwrap(),
+///         ),
+///         (
+///             Utc.with_ymd_and_hms(2020, 1, 1, 13, 0, 0).unwrap(),
+///             Utc.with_ymd_and_hms(2020, 1, 1, 13, 30, 0).unwrap(),
+///         ),
+///     ]
+/// );
+///
+/// let mut out = Vec::new();
+/// ical.export_to(&mut out).unwrap();
+/// let out = String::from_utf8(out).unwrap().replace("\r\n ", "");
+/// assert!(out.contains(
+///     "FREEBUSY;FBTYPE=BUSY:20200101T080000Z/20200101T090000Z,20200101T130000Z/20200101T133000Z\r\n"
+/// ));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FreeBusy {
+    pub dtstart: Option<DateTime<Utc>>,
+    pub dtend: Option<DateTime<Utc>>,
+    pub organizer: Option<Organizer>,
+    /// Busy periods as start and end pairs.
+    pub busy: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl FreeBusy {
+    fn write_component<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "BEGIN:VFREEBUSY\r\n")?;
+        if let Some(organizer) = self.organizer.as_ref() {
+            write_property(writer, &organizer.to_property())?;
+        }
+        if let Some(dtstart) = self.dtstart.as_ref() {
+            write_property(
+                writer,
+                &format!("DTSTART:{}", dtstart.format("%Y%m%dT%H%M%SZ")),
+            )?;
+        }
+        if let Some(dtend) = self.dtend.as_ref() {
+            write_property(writer, &format!("DTEND:{}", dtend.format("%Y%m%dT%H%M%SZ")))?;
+        }
+        if !self.busy.is_empty() {
+            let periods: Vec<String> = self
+                .busy
+                .iter()
+                .map(|(start, end)| {
+                    format!(
+                        "{}/{}",
+                        start.format("%Y%m%dT%H%M%SZ"),
+                        end.format("%Y%m%dT%H%M%SZ")
+                    )
+                })
+                .collect();
+            write_property(
+                writer,
+                &format!("FREEBUSY;FBTYPE=BUSY:{}", periods.join(",")),
+            )?;
+        }
+        write!(writer, "END:VFREEBUSY\r\n")
+    }
+}
+
+/// Parse a PERIOD value, given either as `start/end` or as `start/duration`.
+fn parse_period(value: &str) -> anyhow::Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let (start, end) = value
+        .split_once('/')
+        .with_context(|| format!("invalid period: {}", value))?;
+    let start = parse_date_time(start)?;
+    let end = if end.starts_with(['P', '+', '-']) {
+        start
+            .checked_add_signed(parse_duration(end)?)
+            .with_context(|| format!("invalid period: {}", value))?
+    } else {
+        parse_date_time(end)?
+    };
+    Ok((start, end))
+}
+
+/// An inline `VTIMEZONE` definition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timezone {
+    pub tzid: String,
+    /// The raw content lines of the definition apart from `TZID`, including nested
+    /// `STANDARD`/`DAYLIGHT` components.
+    pub lines: Vec<String>,
+}
+
+/// The `PRODID` of calendars created by [`Calendar::create`] without one.
+pub const DEFAULT_PRODID: &str = "-//web_ical//EN";
+
+/// store the iCalendar and add events from struct `Events`.
+///
+/// With the `serde` feature calendars can be serialized, e.g. to cache them as JSON. Durations
+/// are written as RFC 5545 DURATION values.
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use web_ical::Calendar;
+///
+/// let ical = Calendar::new_from_data(
+///     "BEGIN:VCALENDAR\r\n\
+///      PRODID:-//Test//EN\r\n\
+///      VERSION:2.0\r\n\
+///      BEGIN:VEVENT\r\n\
+///      DTSTART:20190522T090000Z\r\n\
+///      DTEND:20190522T100000Z\r\n\
+///      DTSTAMP:20190501T090000Z\r\n\
+///      UID:1@example.com\r\n\
+///      DESCRIPTION:Sync\r\n\
+///      LOCATION:Office\r\n\
+///      SEQUENCE:0\r\n\
+///      STATUS:CONFIRMED\r\n\
+///      SUMMARY:Sync\r\n\
+///      TRANSP:OPAQUE\r\n\
+///      RRULE:FREQ=WEEKLY;COUNT=4\r\n\
+///      BEGIN:VALARM\r\n\
+///      ACTION:DISPLAY\r\n\
+///      TRIGGER:-PT15M\r\n\
+///      DESCRIPTION:Reminder\r\n\
+///      END:VALARM\r\n\
+///      END:VEVENT\r\n\
+///      END:VCALENDAR\r\n",
+/// )
+/// .unwrap();
+///
+/// let json = serde_json::to_string(&ical).unwrap();
+/// assert!(json.contains("\"prodid\":\"-//Test//EN\""));
+/// assert!(json.contains("\"calscale\":null"));
+/// let back: Calendar = serde_json::from_str(&json).unwrap();
+/// assert_eq!(back.to_ics_string(), ical.to_ics_string());
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calendar {
+    /// The RFC 7986 `NAME` of the calendar, which some clients prefer over `X-WR-CALNAME`.
+    pub name: Option<String>,
+    /// The color to show the calendar in, from the RFC 7986 `COLOR` property or Apple's
+    /// `X-APPLE-CALENDAR-COLOR`, the former taking precedence. It is exported as `COLOR`, while
+    /// the vendor property stays in [`Calendar::extra`].
+    pub color: Option<String>,
+    /// How often subscribers should fetch the calendar again, given in the RFC 7986
+    /// `REFRESH-INTERVAL` property.
+    #[cfg_attr(feature = "serde", serde(default, with = "serde_duration::option"))]
+    pub refresh_interval: Option<Duration>,
+    /// The `PRODID` exactly as found in the feed, colons and all, written back unescaped.
+    pub prodid: String,
+    /// The `VERSION`, which is `2.0` for any calendar following RFC 5545. Of a `minver;maxver`
+    /// range only the highest version is kept. Other versions are parsed the same way, but warned
+    /// about.
+    pub version: String,
+    pub calscale: Option<String>,
+    pub method: Option<Method>,
+    pub x_wr_calname: Option<String>,
+    pub x_wr_timezone: Option<String>,
+    /// Categories of the whole calendar as given by the RFC 7986 `CATEGORIES` property, apart from
+    /// those of the events.
+    pub categories: Vec<String>,
+    pub timezones: Vec<Timezone>,
+    pub events: Vec<Event>,
+    pub journals: Vec<Journal>,
+    pub freebusy: Vec<FreeBusy>,
+    /// Unrecognized properties such as vendor `X-` extensions, as key (including parameters)
+    /// and value pairs which are written back verbatim.
+    pub extra: Vec<(String, String)>,
+    /// The names of the calendar properties in the order they were parsed in. Only recorded with
+    /// [`ParseOptions::preserve_order`].
+    pub property_order: Vec<String>,
+}
+
+/// Compares everything but `property_order`, which only affects how the calendar is written, so
+/// that calendars parsed with [`ParseOptions::preserve_order`] equal those parsed without.
+impl PartialEq for Calendar {
+    fn eq(&self, other: &Calendar) -> bool {
+        let Calendar {
+            name,
+            color,
+            refresh_interval,
+            prodid,
+            version,
+            calscale,
+            method,
+            x_wr_calname,
+            x_wr_timezone,
+            categories,
+            timezones,
+            events,
+            journals,
+            freebusy,
+            extra,
+            property_order: _,
+        } = self;
+        *name == other.name
+            && *color == other.color
+            && *refresh_interval == other.refresh_interval
+            && *prodid == other.prodid
+            && *version == other.version
+            && *calscale == other.calscale
+            && *method == other.method
+            && *x_wr_calname == other.x_wr_calname
+            && *x_wr_timezone == other.x_wr_timezone
+            && *categories == other.categories
+            && *timezones == other.timezones
+            && *events == other.events
+            && *journals == other.journals
+            && *freebusy == other.freebusy
+            && *extra == other.extra
+    }
+}
+
+/// Options controlling how a calendar is exported.
+#[derive(Clone)]
+pub struct ExportOptions {
+    /// Terminate the final `END:VCALENDAR` with a CRLF like every other content line, as RFC 5545
+    /// requires. Turning it off reproduces the output of tools which omit it. Defaults to `true`.
+    pub trailing_crlf: bool,
+    /// Leave out the `ORGANIZER` and `ATTENDEE` properties of the events, to share availability
+    /// without revealing who takes part. Defaults to `false`.
+    pub redact_participants: bool,
+    /// Write the properties in the order they were parsed in, see
+    /// [`ParseOptions::preserve_order`]. Properties not seen while parsing follow in the default
+    /// order. Defaults to `false`.
+    pub preserve_order: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> ExportOptions {
+        ExportOptions {
+            trailing_crlf: true,
+            redact_participants: false,
+            preserve_order: false,
+        }
+    }
+}
+
+/// Options controlling how a calendar is requested, see [`Calendar::new_with_options`].
+#[derive(Clone)]
+pub struct FetchOptions {
+    /// How many redirects to follow before giving up. Defaults to 10.
+    pub max_redirects: usize,
+    /// How long the whole request may take, unlimited if `None`, which is the default.
+    pub timeout: Option<std::time::Duration>,
+}
+
+impl Default for FetchOptions {
+    fn default() -> FetchOptions {
+        FetchOptions {
+            max_redirects: 10,
+            timeout: None,
+        }
+    }
+}
+
+/// The outcome of [`Calendar::new_conditional`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum CalendarFetch {
+    /// The feed is unchanged since it was fetched with the ETag that was sent.
+    NotModified,
+    /// The feed is new or changed. `etag` is the `ETag` to send next time, if the server gave
+    /// one.
+    Modified {
+        calendar: Box<Calendar>,
+        etag: Option<String>,
+    },
+}
+
+/// Something that was skipped or not understood while parsing, see [`Calendar::parse_verbose`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    pub message: String,
+}
+
+impl Warning {
+    fn new(message: String) -> Warning {
+        Warning { message }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// What the parser made of a physical line, see [`Calendar::parse_debug`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineStatus {
+    /// The line was parsed, or kept verbatim like unknown properties are.
+    Recognized,
+    /// The line starts with a space or a tab and was joined to the previous one.
+    Folded,
+    /// The line was dropped, e.g. because it is malformed or part of an unsupported component.
+    Skipped,
+}
+
+/// A physical line of a feed as read by the parser, see [`Calendar::parse_debug`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawLine {
+    /// The line number, starting at 1.
+    pub number: usize,
+    /// The line without its line ending.
+    pub text: String,
+    pub status: LineStatus,
+}
+
+/// A problem found by [`Calendar::validate`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    /// The calendar has an empty `PRODID`.
+    MissingProdid,
+    /// The calendar has an empty `VERSION`.
+    MissingVersion,
+    /// The event at index `event` of [`Calendar::events`] lacks a required property.
+    MissingProperty {
+        event: usize,
+        property: &'static str,
+    },
+    /// The event at index `event` of [`Calendar::events`] has a `DTEND` before its `DTSTART`.
+    EndBeforeStart { event: usize },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::MissingProdid => write!(f, "calendar has no PRODID"),
+            ValidationError::MissingVersion => write!(f, "calendar has no VERSION"),
+            ValidationError::MissingProperty { event, property } => {
+                write!(f, "event {} has no {}", event, property)
+            }
+            ValidationError::EndBeforeStart { event } => {
+                write!(f, "event {} ends before it starts", event)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// A required calendar property missing from a parsed feed. It is returned wrapped in an
+/// [`anyhow::Error`], from which it can be downcast.
+///
+/// ```
+/// use web_ical::{Calendar, ParseError, ParseMode, ParseOptions, DEFAULT_PRODID};
+///
+/// let no_prodid = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n";
+/// let err = Calendar::new_from_data(no_prodid).unwrap_err();
+/// assert_eq!(err.downcast_ref(), Some(&ParseError::MissingProdid));
+///
+/// let no_version = "BEGIN:VCALENDAR\r\nPRODID:-//Test//EN\r\nEND:VCALENDAR\r\n";
+/// let err = Calendar::new_from_data(no_version).unwrap_err();
+/// assert_eq!(err.downcast_ref(), Some(&ParseError::MissingVersion));
+/// assert_eq!(err.to_string(), "calendar has no VERSION");
+///
+/// // lenient parsing fills in defaults instead
+/// let options = ParseOptions {
+///     mode: ParseMode::Lenient,
+///     ..ParseOptions::default()
+/// };
+/// let ical = Calendar::new_from_data_with_options(no_prodid, &options).unwrap();
+/// assert_eq!(ical.prodid, DEFAULT_PRODID);
+///
+/// assert!(Calendar::new_from_data("BEGIN:VEVENT\r\nEND:VEVENT\r\n").is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The calendar has no `PRODID`.
+    MissingProdid,
+    /// The calendar has no `VERSION`.
+    MissingVersion,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingProdid => write!(f, "calendar has no PRODID"),
+            ParseError::MissingVersion => write!(f, "calendar has no VERSION"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// How closely a calendar has to follow RFC 5545 to be parsed, see [`ParseOptions::mode`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Reject values that RFC 5545 and its extensions do not allow instead of keeping them as
+    /// they are, e.g. a `COLOR` that is not a CSS3 color name.
+    Strict,
+    /// Keep or skip values that RFC 5545 does not allow, as documented for each property.
+    #[default]
+    Default,
+    /// Accept deviations from RFC 5545 found in real world feeds, such as the legacy
+    /// `DTSTART:DATE:20190522` form which puts the value type in front of the value.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use web_ical::{Calendar, ParseMode, ParseOptions};
+    ///
+    /// let data = "BEGIN:VCALENDAR\r\n\
+    ///             PRODID:-//Test//EN\r\n\
+    ///             VERSION:2.0\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             UID:1@example.com\r\n\
+    ///             DTSTART:DATE:20190522\r\n\
+    ///             END:VEVENT\r\n\
+    ///             END:VCALENDAR\r\n";
+    ///
+    /// let default = Calendar::new_from_data(data).unwrap();
+    /// assert_eq!(default.events[0].dtstart, None);
+    ///
+    /// let options = ParseOptions {
+    ///     mode: ParseMode::Lenient,
+    ///     ..ParseOptions::default()
+    /// };
+    /// let lenient = Calendar::new_from_data_with_options(data, &options).unwrap();
+    /// assert_eq!(
+    ///     lenient.events[0].dtstart,
+    ///     Some(Utc.with_ymd_and_hms(2019, 5, 22, 0, 0, 0).unwrap())
+    /// );
+    /// assert!(!lenient.has_floating_times());
+    /// ```
+    ///
+    /// Spaces around the colon of hand-edited lines like `SUMMARY : Meeting` are tolerated too.
+    /// The name is always trimmed, the single space after the colon only when lenient:
+    ///
+    /// ```
+    /// use web_ical::{Calendar, ParseMode, ParseOptions};
+    ///
+    /// let data = "BEGIN:VCALENDAR\r\n\
+    ///             PRODID:-//Test//EN\r\n\
+    ///             VERSION:2.0\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             UID:1@example.com\r\n\
+    ///             SUMMARY : Meeting  with  spaces \r\n\
+    ///             END:VEVENT\r\n\
+    ///             END:VCALENDAR\r\n";
+    ///
+    /// let default = Calendar::new_from_data(data).unwrap();
+    /// assert_eq!(default.events[0].summary.as_deref(), Some(" Meeting  with  spaces "));
+    ///
+    /// let options = ParseOptions {
+    ///     mode: ParseMode::Lenient,
+    ///     ..ParseOptions::default()
+    /// };
+    /// let lenient = Calendar::new_from_data_with_options(data, &options).unwrap();
+    /// assert_eq!(lenient.events[0].summary.as_deref(), Some("Meeting  with  spaces "));
+    /// ```
+    Lenient,
+}
+
+/// Options controlling how a calendar is parsed.
+#[derive(Clone, Default)]
+pub struct ParseOptions {
+    /// How strictly RFC 5545 is followed.
+    pub mode: ParseMode,
+    /// Stop with an error once a calendar has more events than this, guarding against huge feeds.
+    ///
+    /// ```
+    /// use web_ical::{Calendar, ParseOptions};
+    ///
+    /// let mut data = "BEGIN:VCALENDAR\r\nPRODID:-//Test//EN\r\nVERSION:2.0\r\n".to_string();
+    /// for i in 0..5 {
+    ///     data.push_str(&format!("BEGIN:VEVENT\r\nUID:{}\r\nEND:VEVENT\r\n", i));
+    /// }
+    /// data.push_str("END:VCALENDAR\r\n");
+    ///
+    /// let options = ParseOptions {
+    ///     max_events: Some(3),
+    ///     ..ParseOptions::default()
+    /// };
+    /// assert!(Calendar::new_from_data_with_options(&data, &options).is_err());
+    ///
+    /// let options = ParseOptions {
+    ///     max_events: Some(5),
+    ///     ..ParseOptions::default()
+    /// };
+    /// let ical = Calendar::new_from_data_with_options(&data, &options).unwrap();
+    /// assert_eq!(ical.events.len(), 5);
+    /// ```
+    pub max_events: Option<usize>,
+    /// Record the order of the properties of the calendar and its events, so that
+    /// [`ExportOptions::preserve_order`] writes them back the way they came in.
+    ///
+    /// ```
+    /// use web_ical::{Calendar, ExportOptions, ParseOptions};
+    ///
+    /// let data = "BEGIN:VCALENDAR\r\n\
+    ///             VERSION:2.0\r\n\
+    ///             PRODID:-//Test//EN\r\n\
+    ///             X-WR-CALNAME:Team\r\n\
+    ///             CALSCALE:GREGORIAN\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             UID:1@example.com\r\n\
+    ///             SUMMARY:Sync\r\n\
+    ///             X-FOO:bar\r\n\
+    ///             DTSTAMP:20190501T090000Z\r\n\
+    ///             DTSTART:20190522T090000Z\r\n\
+    ///             DTEND:20190522T100000Z\r\n\
+    ///             TRANSP:OPAQUE\r\n\
+    ///             BEGIN:VALARM\r\n\
+    ///             ACTION:DISPLAY\r\n\
+    ///             TRIGGER:-PT15M\r\n\
+    ///             END:VALARM\r\n\
+    ///             LOCATION:Office\r\n\
+    ///             END:VEVENT\r\n\
+    ///             END:VCALENDAR\r\n";
+    /// let options = ParseOptions {
+    ///     preserve_order: true,
+    ///     ..ParseOptions::default()
+    /// };
+    /// let ical = Calendar::new_from_data_with_options(data, &options).unwrap();
+    ///
+    /// let options = ExportOptions {
+    ///     preserve_order: true,
+    ///     ..ExportOptions::default()
+    /// };
+    /// let mut out = Vec::new();
+    /// ical.export_to_with_options(&mut out, &options).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), data);
+    ///
+    /// // the default order is used otherwise
+    /// assert!(ical.to_ics_string().starts_with("BEGIN:VCALENDAR\r\nPRODID:-//Test//EN\r\n"));
+    /// // and the order doesn't take part in comparisons
+    /// assert_eq!(ical, Calendar::new_from_data(data).unwrap());
+    /// ```
+    pub preserve_order: bool,
+}
+
+impl ParseOptions {
+    fn lenient(&self) -> bool {
+        self.mode == ParseMode::Lenient
+    }
+
+    fn strict(&self) -> bool {
+        self.mode == ParseMode::Strict
+    }
+}
+
+macro_rules! assign_if_ok {
+    ($lvalue:expr, $rvalue:expr) => {
+        if let Ok(rvalue_ok) = $rvalue {
+            $lvalue = Some(rvalue_ok);
+        }
+    };
+}
+
+/// The reader the parser takes its lines from, which can record them for
+/// [`Calendar::parse_debug`].
+struct LineReader<R> {
+    inner: R,
+    /// The physical lines read so far, `None` unless recording.
+    lines: Option<Vec<RawLine>>,
+    /// The start of the physical line being read.
+    partial: Vec<u8>,
+    /// The index in `lines` of the first physical line of the current content line.
+    current: usize,
+}
+
+impl<R: BufRead> LineReader<R> {
+    fn new(inner: R) -> LineReader<R> {
+        LineReader {
+            inner,
+            lines: None,
+            partial: vec![],
+            current: 0,
+        }
+    }
+
+    fn recording(inner: R) -> LineReader<R> {
+        LineReader {
+            lines: Some(vec![]),
+            ..LineReader::new(inner)
+        }
+    }
+
+    /// Mark the physical lines read from now on as part of a new content line.
+    fn start_line(&mut self) {
+        if let Some(lines) = &self.lines {
+            self.current = lines.len();
+        }
+    }
+
+    /// Mark the physical lines of the current content line as skipped.
+    fn skip_line(&mut self) {
+        if let Some(lines) = &mut self.lines {
+            for line in &mut lines[self.current..] {
+                line.status = LineStatus::Skipped;
+            }
+        }
+    }
+
+    fn push_line(lines: &mut Vec<RawLine>, partial: &mut Vec<u8>) {
+        let text = String::from_utf8_lossy(partial);
+        let text = text.strip_suffix('\r').unwrap_or(&text).to_string();
+        let status = if text.starts_with([' ', '\t']) {
+            LineStatus::Folded
+        } else {
+            LineStatus::Recognized
+        };
+        lines.push(RawLine {
+            number: lines.len() + 1,
+            text,
+            status,
+        });
+        partial.clear();
+    }
+
+    /// The recorded lines, including a last one without line ending.
+    fn into_lines(mut self) -> Vec<RawLine> {
+        let mut lines = self.lines.take().unwrap_or_default();
+        if !self.partial.is_empty() {
+            Self::push_line(&mut lines, &mut self.partial);
+        }
+        lines
+    }
+}
+
+impl<R: BufRead> Read for LineReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let buf = self.fill_buf()?;
+        let len = buf.len().min(out.len());
+        out[..len].copy_from_slice(&buf[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for LineReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let (Some(lines), true) = (&mut self.lines, amt > 0) {
+            // the consumed bytes are still in the buffer, so this doesn't read
+            if let Ok(buf) = self.inner.fill_buf() {
+                for &byte in &buf[..amt] {
+                    if byte == b'\n' {
+                        Self::push_line(lines, &mut self.partial);
+                    } else {
+                        self.partial.push(byte);
+                    }
+                }
+            }
+        }
+        self.inner.consume(amt);
+    }
+}
+
+/// Remove the new line character of a line read as bytes, see [`strip_line_ending`].
+fn strip_line_ending_bytes(buf: &mut Vec<u8>) {
+    if buf.ends_with(b"\n") {
+        buf.pop();
+        if buf.ends_with(b"\r") {
+            buf.pop();
+        }
+    }
+}
+
+/// Remove the new line character, the last line may come without one.
+fn strip_line_ending(buf: &mut String) {
+    if buf.ends_with("\r\n") {
+        buf.pop();
+        buf.pop();
+    } else if buf.ends_with('\n') {
+        buf.pop();
+    }
+}
+
+/// Read the next content line into `buf` without its line ending, failing at the end of the
+/// input. Folded lines are joined back together.
+///
+/// The lines are joined as bytes, so a fold may even split a multi-octet UTF-8 character as
+/// RFC 5545 section 3.1 allows.
+fn next_line<R: BufRead>(raw: &mut LineReader<R>, buf: &mut String) -> anyhow::Result<()> {
+    raw.start_line();
+    let mut line = std::mem::take(buf).into_bytes();
+    line.clear();
+    if raw.read_until(b'\n', &mut line)? == 0 {
+        return Err(anyhow::Error::new(io::Error::from(
+            ErrorKind::UnexpectedEof,
+        )));
+    }
+    strip_line_ending_bytes(&mut line);
+    // a line starting with a space or tab continues the previous one
+    while matches!(raw.fill_buf()?.first(), Some(b' ') | Some(b'\t')) {
+        raw.consume(1);
+        raw.read_until(b'\n', &mut line)?;
+        strip_line_ending_bytes(&mut line);
+    }
+    *buf = String::from_utf8(line).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+    join_soft_line_breaks(raw, buf)
+}
+
+/// Join the lines of a property with `ENCODING=QUOTED-PRINTABLE`, as found in old Outlook
+/// exports, which ends its lines in a soft line break (`=`) instead of folding them. The value
+/// itself is decoded by [`text_value`].
+fn join_soft_line_breaks<R: BufRead>(
+    raw: &mut LineReader<R>,
+    buf: &mut String,
+) -> anyhow::Result<()> {
+    // next to no property is encoded, so only those mentioning the encoding are parsed
+    let mentioned = buf
+        .as_bytes()
+        .windows(QUOTED_PRINTABLE.len())
+        .any(|word| word.eq_ignore_ascii_case(QUOTED_PRINTABLE.as_bytes()));
+    let encoded = mentioned
+        && split_property(buf, false)
+            .is_some_and(|(key, _)| is_quoted_printable(&parse_params(key).1));
+    if !encoded {
+        return Ok(());
+    }
+    while buf.ends_with('=') {
+        buf.pop();
+        if raw.read_line(buf)? == 0 {
+            break;
+        }
+        strip_line_ending(buf);
+    }
+    Ok(())
+}
+
+const QUOTED_PRINTABLE: &str = "QUOTED-PRINTABLE";
+
+/// Whether a property's value has `ENCODING=QUOTED-PRINTABLE`.
+fn is_quoted_printable(params: &[(String, String)]) -> bool {
+    param(params, "ENCODING").is_some_and(|val| val.eq_ignore_ascii_case(QUOTED_PRINTABLE))
+}
+
+/// The TEXT value of a property, unescaped or decoded according to its `ENCODING=QUOTED-PRINTABLE`
+/// and `CHARSET` parameters.
+fn text_value(params: &[(String, String)], value: &str) -> String {
+    if !is_quoted_printable(params) {
+        return unescape_text(value);
+    }
+    // quoted-printable text isn't escaped, its line breaks are encoded as `=0D=0A`
+    decode_text(&decode_hex_escapes(value, b'='), param(params, "CHARSET")).replace("\r\n", "\n")
+}
+
+fn parse_cal<R: BufRead>(
+    raw: R,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> anyhow::Result<Calendar> {
+    let raw = &mut LineReader::new(raw);
+    read_begin_vcalendar(raw)?;
+
+    // anything after the first calendar is ignored, see `parse_many_cals`
+    parse_vcalendar(raw, options, false, warnings)
+}
+
+/// Read the `BEGIN:VCALENDAR` line a calendar has to start with.
+fn read_begin_vcalendar<R: BufRead>(raw: &mut LineReader<R>) -> anyhow::Result<()> {
+    let mut buf = String::new();
+    raw.read_line(&mut buf)?;
+    let line = buf.trim_end_matches(['\r', '\n']);
+    if !line.eq_ignore_ascii_case("BEGIN:VCALENDAR") {
+        anyhow::bail!("expected BEGIN:VCALENDAR, found: {}", line);
+    }
+    Ok(())
+}
+
+/// Parse all calendars of a stream of concatenated `VCALENDAR` objects.
+fn parse_many_cals<R: BufRead>(
+    raw: R,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> anyhow::Result<Vec<Calendar>> {
+    let raw = &mut LineReader::new(raw);
+    let mut buf = String::new();
+    let mut calendars = vec![];
+    loop {
+        buf.clear();
+        if raw.read_line(&mut buf)? == 0 {
+            return Ok(calendars);
+        }
+        // concatenated files may each bring their own byte order mark
+        let line = buf.trim_start_matches('\u{feff}').trim();
+        if line.is_empty() {
+            continue;
+        }
+        if !line.eq_ignore_ascii_case("BEGIN:VCALENDAR") {
+            anyhow::bail!("expected BEGIN:VCALENDAR, found: {}", line);
+        }
+        calendars.push(parse_vcalendar(raw, options, false, warnings)?);
+    }
+}
+
+/// Parse a calendar whose `BEGIN:VCALENDAR` line has already been read.
+///
+/// With `headers_only` parsing stops at the first event, leaving the rest of the input unread.
+fn parse_vcalendar<R: BufRead>(
+    raw: &mut LineReader<R>,
+    options: &ParseOptions,
+    headers_only: bool,
+    warnings: &mut Vec<Warning>,
+) -> anyhow::Result<Calendar> {
+    let mut buf = String::new();
+
+    // FIXME: put all this into a builder struct!
+    let mut prodid = None;
+    let mut version = None;
+    let mut calscale = None;
+    let mut method = None;
+    let mut name = None;
+    let mut color = None;
+    let mut refresh_interval = None;
+    let mut x_wr_calname = None;
+    let mut x_wr_timezone = None;
+    let mut categories = vec![];
+
+    let mut timezones = vec![];
+    let mut events: Vec<Event> = vec![];
+    let mut journals = vec![];
+    let mut freebusy = vec![];
+    let mut extra = vec![];
+    let mut property_order = vec![];
+    loop {
+        next_line(raw, &mut buf)?;
+        if buf.eq_ignore_ascii_case("END:VCALENDAR")
+            || (headers_only && buf.eq_ignore_ascii_case("BEGIN:VEVENT"))
+        {
+            for ev in events.iter_mut().filter(|ev| ev.display_tz.is_none()) {
+                ev.display_tz = x_wr_timezone.clone();
+            }
+            if options.lenient() {
+                if prodid.is_none() {
+                    warnings.push(Warning::new("missing prodid".to_string()));
+                    prodid = Some(DEFAULT_PRODID.to_string());
+                }
+                if version.is_none() {
+                    warnings.push(Warning::new("missing version, assuming 2.0".to_string()));
+                    version = Some("2.0".to_string());
+                }
+            }
+            return Ok(Calendar {
+                prodid: prodid.ok_or(ParseError::MissingProdid)?,
+                version: version.ok_or(ParseError::MissingVersion)?,
+                calscale,
+                method,
+                x_wr_calname,
+                x_wr_timezone,
+                categories,
+                timezones,
+                events,
+                journals,
+                freebusy,
+                name,
+                color,
+                refresh_interval,
+                extra,
+                property_order,
+            });
+        }
+        let (raw_key, value) = if let Some(kv) = split_property(&buf, options.lenient()) {
+            kv
+        } else {
+            warnings.push(Warning::new(format!("Found bad line: {}", buf)));
+            raw.skip_line();
+            continue;
+        };
+        // none of the calendar properties make use of parameters yet
+        let (key, _) = parse_params(raw_key);
+        let key = key.to_ascii_uppercase();
+        // components are always written after the properties
+        if options.preserve_order && key != "BEGIN" {
+            property_order.push(key.clone());
+        }
+        match key.as_str() {
+            "NAME" => {
+                name = Some(value.to_string());
+            }
+            "COLOR" => {
+                color = Some(value.to_string());
+            }
+            "X-APPLE-CALENDAR-COLOR" => {
+                color.get_or_insert_with(|| value.to_string());
+                // Apple's clients only look at their own property, so keep it as well
+                extra.push((raw_key.to_string(), value.to_string()));
+            }
+            "REFRESH-INTERVAL" => {
+                assign_if_ok!(refresh_interval, parse_duration(value));
+            }
+            "PRODID" => {
+                if prodid.is_some() {
+                    warnings.push(Warning::new(format!("duplicate prodid: {}", value)));
+                } else {
+                    prodid = Some(value.to_string());
+                }
+            }
+            "VERSION" => {
+                // RFC 2445 allowed a `minver;maxver` range, the highest version is what counts
+                let max_version = value.rsplit(';').next().unwrap_or(value).trim();
+                if max_version != "2.0" {
+                    warnings.push(Warning::new(format!("unsupported version: {}", value)));
+                }
+                if version.is_some() {
+                    warnings.push(Warning::new(format!("duplicate version: {}", value)));
+                } else {
+                    version = Some(max_version.to_string());
+                }
+            }
+            "CALSCALE" => {
+                calscale = Some(value.to_string());
+            }
+            "METHOD" => {
+                method = Some(Method::from(value));
+            }
+            "X-WR-CALNAME" => {
+                x_wr_calname = Some(value.to_string());
+            }
+            "X-WR-TIMEZONE" => {
+                x_wr_timezone = Some(value.to_string());
+            }
+            "CATEGORIES" => {
+                categories.extend(split_text_list(value));
+            }
+            "BEGIN" => {
+                let value = value.to_ascii_uppercase();
+                let value = value.as_str();
+                if value == "VEVENT" {
+                    if options.max_events == Some(events.len()) {
+                        anyhow::bail!("calendar has more than {} events", events.len());
+                    }
+                    events.push(parse_event(raw, options, warnings)?);
+                } else if value == "VJOURNAL" {
+                    journals.push(parse_journal(raw, options, warnings)?);
+                } else if value == "VFREEBUSY" {
+                    freebusy.push(parse_freebusy(raw, warnings)?);
+                } else if value == "VTIMEZONE" {
+                    timezones.push(parse_timezone(raw)?);
+                } else {
+                    // FIXME: todo support this!
+                    // skip the component so its properties aren't taken for the calendar's
+                    warnings.push(Warning::new(format!(
+                        "skipped unhandled component: {}",
+                        value
+                    )));
+                    skip_component(raw, value)?;
+                }
+            }
+            _ => {
+                warnings.push(Warning::new(format!(
+                    "unhandled calendar property: {}",
+                    key
+                )));
+                extra.push((raw_key.to_string(), value.to_string()));
+            }
+        }
+    }
+}
+
+/// Skip the rest of a component which was opened with `BEGIN:{name}`.
+fn skip_component<R: BufRead>(raw: &mut LineReader<R>, name: &str) -> anyhow::Result<()> {
+    let end = format!("END:{}", name);
+    let mut buf = String::new();
+    // the BEGIN line
+    raw.skip_line();
+    loop {
+        next_line(raw, &mut buf)?;
+        raw.skip_line();
+        if buf.eq_ignore_ascii_case(&end) {
+            return Ok(());
+        }
+    }
+}
+
+fn parse_timezone<R: BufRead>(raw: &mut LineReader<R>) -> anyhow::Result<Timezone> {
+    let mut buf = String::new();
+    let mut tzid = None;
+    let mut lines = vec![];
+    loop {
+        next_line(raw, &mut buf)?;
+        if buf.eq_ignore_ascii_case("END:VTIMEZONE") {
+            return Ok(Timezone {
+                tzid: tzid.context("a timezone needs a tzid")?,
+                lines,
+            });
+        }
+        match split_property(&buf, false) {
+            Some((key, val)) if key.eq_ignore_ascii_case("TZID") => tzid = Some(val.to_string()),
+            _ => lines.push(buf.clone()),
+        }
+    }
+}
+
+fn parse_journal<R: BufRead>(
+    raw: &mut LineReader<R>,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> anyhow::Result<Journal> {
+    let mut buf = String::new();
+    let mut journal = Journal {
+        uid: None,
+        dtstamp: None,
+        dtstart: None,
+        all_day: false,
+        summary: None,
+        description: None,
+    };
+    loop {
+        next_line(raw, &mut buf)?;
+        if buf.eq_ignore_ascii_case("END:VJOURNAL") {
+            return Ok(journal);
+        }
+        let (key, value) = if let Some(kv) = split_property(&buf, options.lenient()) {
+            kv
+        } else {
+            warnings.push(Warning::new(format!("Found bad line: {}", buf)));
+            raw.skip_line();
+            continue;
+        };
+        let (key, params) = parse_params(key);
+        let key = key.to_ascii_uppercase();
+        match key.as_str() {
+            "UID" => journal.uid = Some(value.to_string()),
+            "DTSTAMP" => assign_if_ok!(journal.dtstamp, parse_date_time(value)),
+            "DTSTART" => {
+                let (is_date, value) = date_value(&params, value, options);
+                journal.all_day = is_date;
+                let tzid = param(&params, "TZID");
+                assign_if_ok!(
+                    journal.dtstart,
+                    parse_date_or_date_time(is_date, value, tzid)
+                );
+            }
+            "SUMMARY" => journal.summary = Some(text_value(&params, value)),
+            "DESCRIPTION" => journal.description = Some(text_value(&params, value)),
+            other => {
+                warnings.push(Warning::new(format!(
+                    "unhandled journal key, value: \"{}\": \"{}\"",
+                    other, value
+                )));
+                raw.skip_line();
+            }
+        }
+    }
+}
+
+fn parse_freebusy<R: BufRead>(
+    raw: &mut LineReader<R>,
+    warnings: &mut Vec<Warning>,
+) -> anyhow::Result<FreeBusy> {
+    let mut buf = String::new();
+    let mut freebusy = FreeBusy {
+        dtstart: None,
+        dtend: None,
+        organizer: None,
+        busy: vec![],
+    };
+    loop {
+        next_line(raw, &mut buf)?;
+        if buf.eq_ignore_ascii_case("END:VFREEBUSY") {
+            return Ok(freebusy);
+        }
+        let (key, value) = if let Some(kv) = split_property(&buf, false) {
+            kv
+        } else {
+            warnings.push(Warning::new(format!("Found bad line: {}", buf)));
+            raw.skip_line();
+            continue;
+        };
+        let (key, params) = parse_params(key);
+        let key = key.to_ascii_uppercase();
+        match key.as_str() {
+            "DTSTART" => assign_if_ok!(freebusy.dtstart, parse_date_time(value)),
+            "DTEND" => assign_if_ok!(freebusy.dtend, parse_date_time(value)),
+            "ORGANIZER" => freebusy.organizer = Some(Organizer::parse(&params, value)),
+            "FREEBUSY" => {
+                // periods without a FBTYPE are busy as well
+                if param(&params, "FBTYPE")
+                    .map_or(true, |fbtype| fbtype.eq_ignore_ascii_case("BUSY"))
+                {
+                    freebusy.busy.extend(
+                        value
+                            .split(',')
+                            .filter_map(|period| parse_period(period).ok()),
+                    );
+                }
+            }
+            other => {
+                warnings.push(Warning::new(format!(
+                    "unhandled freebusy key, value: \"{}\": \"{}\"",
+                    other, value
+                )));
+                raw.skip_line();
+            }
+        }
+    }
+}
+
+fn parse_alarm<R: BufRead>(
+    raw: &mut LineReader<R>,
+    warnings: &mut Vec<Warning>,
+) -> anyhow::Result<Alarm> {
+    let mut buf = String::new();
+    let mut alarm = Alarm {
+        action: String::new(),
+        trigger: None,
+        description: None,
+    };
+    loop {
+        next_line(raw, &mut buf)?;
+        if buf.eq_ignore_ascii_case("END:VALARM") {
+            return Ok(alarm);
+        }
+        let (key, value) = if let Some(kv) = split_property(&buf, false) {
+            kv
+        } else {
+            warnings.push(Warning::new(format!("Found bad line: {}", buf)));
+            raw.skip_line();
+            continue;
+        };
+        let (key, params) = parse_params(key);
+        let key = key.to_ascii_uppercase();
+        match key.as_str() {
+            "ACTION" => alarm.action = value.to_string(),
+            "TRIGGER" => {
+                let absolute = param(&params, "VALUE")
+                    .is_some_and(|val| val.eq_ignore_ascii_case("DATE-TIME"));
+                alarm.trigger = if absolute {
+                    parse_date_time(value).ok().map(Trigger::Absolute)
+                } else {
+                    parse_duration(value).ok().map(Trigger::Relative)
+                };
+            }
+            "DESCRIPTION" => alarm.description = Some(text_value(&params, value)),
+            other => {
+                warnings.push(Warning::new(format!(
+                    "unhandled alarm key, value: \"{}\": \"{}\"",
+                    other, value
+                )));
+                raw.skip_line();
+            }
+        }
+    }
+}
+
+fn parse_event<R: BufRead>(
+    raw: &mut LineReader<R>,
+    options: &ParseOptions,
+    warnings: &mut Vec<Warning>,
+) -> anyhow::Result<Event> {
+    let mut buf = String::new();
+    let mut ev = Event::empty();
+    loop {
+        next_line(raw, &mut buf)?;
+        if buf.eq_ignore_ascii_case("END:VEVENT") {
+            if options.lenient() && ev.ends_before_start() {
+                warnings.push(Warning::new(format!(
+                    "dropped DTEND before DTSTART of event {}",
+                    ev.uid.as_deref().unwrap_or_default()
+                )));
+                ev.dtend = None;
+            }
+            return Ok(ev);
+        }
+        let (raw_key, value) = if let Some(kv) = split_property(&buf, options.lenient()) {
+            kv
+        } else {
+            warnings.push(Warning::new(format!("Found bad line: {}", buf)));
+            raw.skip_line();
+            continue;
+        };
+        let (key, params) = parse_params(raw_key);
+        let key = key.to_ascii_uppercase();
+        if options.preserve_order {
+            ev.property_order.push(key.clone());
+        }
+        match key.as_str() {
+            "CLASS" => {
+                ev.class = Some(Classification::from(value));
+            }
+            "GEO" => {
+                ev.geo = parse_geo(value);
+                if ev.geo.is_none() {
+                    if options.strict() {
+                        anyhow::bail!("malformed geo: {}", value);
+                    }
+                    warnings.push(Warning::new(format!("Found malformed geo: {}", value)));
+                }
+            }
+            "PRIORITY" => {
+                ev.priority = value.trim().parse().ok().filter(|priority| *priority <= 9);
+                if ev.priority.is_none() {
+                    if options.strict() {
+                        anyhow::bail!("invalid priority: {}", value);
+                    }
+                    warnings.push(Warning::new(format!("Found invalid priority: {}", value)));
+                }
+            }
+            "PERCENT-COMPLETE" => {
+                ev.percent_complete = value.trim().parse().ok().filter(|percent| *percent <= 100);
+                if ev.percent_complete.is_none() {
+                    if options.strict() {
+                        anyhow::bail!("invalid percent complete: {}", value);
+                    }
+                    warnings.push(Warning::new(format!(
+                        "Found invalid percent complete: {}",
+                        value
+                    )));
+                }
+            }
+            "COMPLETED" => {
+                assign_if_ok!(ev.completed, parse_date_time(value));
+            }
+            "RECURRENCE-ID" | "RECUR-ID" => {
+                let (is_date, value) = date_value(&params, value, options);
+                let tzid = param(&params, "TZID");
+                assign_if_ok!(ev.recur_id, parse_date_or_date_time(is_date, value, tzid));
+            }
+            "URL" => {
+                ev.url = Some(value.to_string());
+            }
+            "UID" => {
+                ev.uid = Some(value.to_string());
+            }
+            "COLOR" => {
+                if options.strict() && css_color(value).is_none() {
+                    anyhow::bail!("unknown color: {}", value);
+                }
+                ev.color = Some(value.to_string());
+            }
+            "DESCRIPTION" => {
+                ev.description = Some(text_value(&params, value));
+                let altrep = param(&params, "ALTREP");
+                ev.description_html = altrep.and_then(html_from_data_uri);
+                ev.description_altrep = altrep
+                    .filter(|_| ev.description_html.is_none())
+                    .map(str::to_string);
+                ev.description_language = param(&params, "LANGUAGE").map(str::to_string);
+            }
+            "LOCATION" => {
+                ev.location = Some(text_value(&params, value));
+                // the value is kept decoded
+                ev.location_params = params
+                    .into_iter()
+                    .filter(|(name, _)| name != "ENCODING" && name != "CHARSET")
+                    .collect();
+            }
+            "SEQUENCE" => {
+                assign_if_ok!(ev.sequence, value.trim().parse::<u32>());
+            }
+            "STATUS" => {
+                ev.status = Some(EventStatus::from(value));
+            }
+            "SUMMARY" => {
+                ev.summary = Some(text_value(&params, value));
+                ev.summary_language = param(&params, "LANGUAGE").map(str::to_string);
+            }
+            "CATEGORIES" => {
+                ev.categories.extend(split_text_list(value));
+            }
+            "CONTACT" => {
+                ev.contact.push(text_value(&params, value));
+            }
+            "COMMENT" => {
+                ev.comment.push(text_value(&params, value));
+            }
+            "RESOURCES" => {
+                ev.resources.extend(split_text_list(value));
+            }
+            "TRANSP" => {
+                ev.transp = TimeTransparency::from(value);
+            }
+            "ORGANIZER" => {
+                ev.organizer = Some(Organizer::parse(&params, value));
+            }
+            "ATTACH" => ev.attachments.push(Attachment::parse(&params, value)),
+            "ATTENDEE" => {
+                ev.attendees.push(Attendee::parse(&params, value));
+            }
+            "BEGIN" => {
+                if value.eq_ignore_ascii_case("VALARM") {
+                    ev.alarms.push(parse_alarm(raw, warnings)?);
+                } else {
+                    // skip the component so its properties aren't taken for the event's
+                    warnings.push(Warning::new(format!(
+                        "unhandled component in event: {}",
+                        value
+                    )));
+                    skip_component(raw, value)?;
+                }
+            }
+            "RRULE" => {
+                ev.repeat = Repeat::parse(value, options, warnings)?;
+                if ev.repeat.is_none() {
+                    warnings.push(Warning::new(format!("Found weird rrule: {}", value)));
+                }
+            }
+            "DTSTART" => {
+                let (is_date, value) = date_value(&params, value, options);
+                ev.floating |= is_floating(&params, value);
+                ev.display_tz = param(&params, "TZID").map(str::to_string);
+                ev.all_day = is_date;
+                let tzid = param(&params, "TZID");
+                assign_if_ok!(ev.dtstart, parse_date_or_date_time(is_date, value, tzid));
+            }
+            "DTEND" => {
+                let (is_date, value) = date_value(&params, value, options);
+                ev.floating |= is_floating(&params, value);
+                let tzid = param(&params, "TZID");
+                assign_if_ok!(ev.dtend, parse_date_or_date_time(is_date, value, tzid));
+            }
+            "DURATION" => {
+                assign_if_ok!(ev.duration, parse_duration(value));
+            }
+            "RDATE" => {
+                let (is_date, value) = date_value(&params, value, options);
+                ev.rdates
+                    .extend(parse_date_list(is_date, value, param(&params, "TZID")));
+            }
+            "EXDATE" => {
+                let (is_date, value) = date_value(&params, value, options);
+                ev.exdates
+                    .extend(parse_date_list(is_date, value, param(&params, "TZID")));
+            }
+            "DTSTAMP" => {
+                assign_if_ok!(ev.dtstamp, parse_date_time(value));
+            }
+            "CREATED" => {
+                assign_if_ok!(ev.created, parse_date_time(value));
+            }
+            "LAST-MODIFIED" => {
+                assign_if_ok!(ev.last_modified, parse_date_time(value));
+            }
+            _ => {
+                warnings.push(Warning::new(format!("unhandled event property: {}", key)));
+                ev.extra.push((raw_key.to_string(), value.to_string()));
+            }
+        }
+    }
+}
+
+/// Write the content lines in `properties` in the order their names have in `order`.
+///
+/// The n-th property of a name takes the position of the n-th occurrence of that name, folded
+/// lines and nested components, which are named `BEGIN`, moving as a whole. Properties missing
+/// from `order` follow in the order they are given in.
+fn write_in_order<W: Write>(writer: &mut W, properties: &[u8], order: &[String]) -> io::Result<()> {
+    let text = String::from_utf8_lossy(properties);
+    let mut groups: Vec<(String, String)> = vec![];
+    let mut depth = 0;
+    for line in text.split_terminator("\r\n") {
+        let name = line.split([';', ':']).next().unwrap_or_default();
+        let name = name.to_ascii_uppercase();
+        match groups.last_mut() {
+            Some((_, group)) if depth > 0 || line.starts_with([' ', '\t']) => {
+                group.push_str(line);
+                group.push_str("\r\n");
+            }
+            _ => groups.push((name.clone(), format!("{}\r\n", line))),
+        }
+        match name.as_str() {
+            "BEGIN" => depth += 1,
+            "END" => depth -= 1,
+            _ => (),
+        }
+    }
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut positioned: Vec<(usize, String)> = groups
+        .into_iter()
+        .map(|(name, group)| {
+            let nth = seen.entry(name.clone()).or_default();
+            let position = order
+                .iter()
+                .enumerate()
+                .filter(|(_, ordered)| **ordered == name)
+                .nth(*nth)
+                .map_or(usize::MAX, |(position, _)| position);
+            *nth += 1;
+            (position, group)
+        })
+        .collect();
+    positioned.sort_by_key(|(position, _)| *position);
+    for (_, group) in positioned {
+        writer.write_all(group.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// The `;LANGUAGE=...` parameter for `language`, empty without one.
+fn language_param(language: Option<&str>) -> String {
+    language.map_or_else(String::new, |language| {
+        format!(";LANGUAGE={}", quote_param(language))
+    })
+}
+
+/// Write the properties and alarms of `event`, in their default order.
+fn write_event_properties<W: Write>(
+    writer: &mut W,
+    event: &Event,
+    options: &ExportOptions,
+) -> io::Result<()> {
+    // all-day events keep their DATE values, DTEND being the exclusive next day
+    let (date_param, date_format) = if event.all_day {
+        (";VALUE=DATE", "%Y%m%d")
+    } else {
+        ("", "%Y%m%dT%H%M%SZ")
+    };
+    // iTIP messages may reference an event by its UID only, so any property can be missing
+    if let Some(dtstart) = event.dtstart.as_ref() {
+        write_property(
+            writer,
+            &format!("DTSTART{}:{}", date_param, dtstart.format(date_format)),
+        )?;
+    }
+    if let Some(dtend) = event.dtend.as_ref() {
+        write_property(
+            writer,
+            &format!("DTEND{}:{}", date_param, dtend.format(date_format)),
+        )?;
+    }
+    if let Some(duration) = event.duration {
+        write_property(writer, &format!("DURATION:{}", format_duration(duration)))?;
+    }
+    if let Some(dtstamp) = event.dtstamp.as_ref() {
+        write_property(
+            writer,
+            &format!("DTSTAMP:{}", dtstamp.format("%Y%m%dT%H%M%SZ")),
+        )?;
+    }
+    if let Some(uid) = event.uid.as_ref() {
+        write_property(writer, &format!("UID:{}", uid))?;
+    }
+    if let Some(created) = event.created.as_ref() {
+        write_property(
+            writer,
+            &format!("CREATED:{}", created.format("%Y%m%dT%H%M%SZ")),
+        )?;
+    }
+    let altrep = match (&event.description_html, &event.description_altrep) {
+        (Some(html), _) => format!(";ALTREP=\"data:text/html,{}\"", percent_encode(html)),
+        (None, Some(uri)) => format!(";ALTREP=\"{}\"", uri),
+        (None, None) => String::new(),
+    };
+    if let Some(description) = event.description.as_ref() {
+        write_property(
+            writer,
+            &format!(
+                "DESCRIPTION{}{}:{}",
+                altrep,
+                language_param(event.description_language.as_deref()),
+                escape_text(description)
+            ),
+        )?;
+    }
+    if let Some(last_modified) = event.last_modified.as_ref() {
+        write_property(
+            writer,
+            &format!("LAST-MODIFIED:{}", last_modified.format("%Y%m%dT%H%M%SZ")),
+        )?;
+    }
+    if let Some(location) = event.location.as_ref() {
+        write_property(
+            writer,
+            &format!(
+                "LOCATION{}:{}",
+                format_params(&event.location_params),
+                escape_text(location)
+            ),
+        )?;
+    }
+    if let Some(sequence) = event.sequence.as_ref() {
+        write_property(writer, &format!("SEQUENCE:{}", sequence))?;
+    }
+    if let Some(status) = event.status.as_ref() {
+        write_property(writer, &format!("STATUS:{}", status))?;
+    }
+    if let Some(summary) = event.summary.as_ref() {
+        write_property(
+            writer,
+            &format!(
+                "SUMMARY{}:{}",
+                language_param(event.summary_language.as_deref()),
+                escape_text(summary)
+            ),
+        )?;
+    }
+    write_property(writer, &format!("TRANSP:{}", event.transp))?;
+    if let Some(repeat) = event.repeat.as_ref() {
+        write_property(
+            writer,
+            &format!("RRULE:{}", repeat.rrule_value(event.all_day)),
+        )?;
+    }
+    for rdate in &event.rdates {
+        write_property(
+            writer,
+            &format!("RDATE{}:{}", date_param, rdate.format(date_format)),
+        )?;
+    }
+    for exdate in &event.exdates {
+        write_property(
+            writer,
+            &format!("EXDATE{}:{}", date_param, exdate.format(date_format)),
+        )?;
+    }
+    if let Some(class) = event.class.as_ref() {
+        write_property(writer, &format!("CLASS:{}", class))?;
+    }
+    if let Some((lat, lon)) = event.geo {
+        write_property(writer, &format!("GEO:{};{}", lat, lon))?;
+    }
+    if let Some(priority) = event.priority.as_ref() {
+        write_property(writer, &format!("PRIORITY:{}", priority))?;
+    }
+    if let Some(percent_complete) = event.percent_complete.as_ref() {
+        write_property(writer, &format!("PERCENT-COMPLETE:{}", percent_complete))?;
+    }
+    if let Some(completed) = event.completed.as_ref() {
+        write_property(
+            writer,
+            &format!("COMPLETED:{}", completed.format("%Y%m%dT%H%M%SZ")),
+        )?;
+    }
+    if let Some(recur_id) = event.recur_id.as_ref() {
+        write_property(
+            writer,
+            &format!(
+                "RECURRENCE-ID{}:{}",
+                date_param,
+                recur_id.format(date_format)
+            ),
+        )?;
+    }
+    if let Some(url) = event.url.as_ref() {
+        write_property(writer, &format!("URL:{}", url))?;
+    }
+    if !event.categories.is_empty() {
+        let categories: Vec<String> = event
+            .categories
+            .iter()
+            .map(|cat| escape_text(cat))
+            .collect();
+        write_property(writer, &format!("CATEGORIES:{}", categories.join(",")))?;
+    }
+    for contact in &event.contact {
+        let line = format!("CONTACT:{}", escape_text(contact));
+        write_property(writer, &line)?;
+    }
+    for comment in &event.comment {
+        let line = format!("COMMENT:{}", escape_text(comment));
+        write_property(writer, &line)?;
+    }
+    if !event.resources.is_empty() {
+        let resources: Vec<String> = event.resources.iter().map(|res| escape_text(res)).collect();
+        let line = format!("RESOURCES:{}", resources.join(","));
+        write_property(writer, &line)?;
+    }
+    if let Some(color) = event.color.as_ref() {
+        write_property(writer, &format!("COLOR:{}", color))?;
+    }
+    if !options.redact_participants {
+        if let Some(organizer) = event.organizer.as_ref() {
+            write_property(writer, &organizer.to_property())?;
+        }
+        for attendee in &event.attendees {
+            write_property(writer, &attendee.to_property())?;
+        }
+    }
+    for attachment in &event.attachments {
+        write_property(writer, &attachment.to_property())?;
+    }
+    for (key, value) in &event.extra {
+        write_property(writer, &format!("{}:{}", key, value))?;
+    }
+    for alarm in &event.alarms {
+        alarm.write_component(writer)?;
+    }
+    Ok(())
+}
+
+/// Decode raw feed bytes, `charset` being the one announced by the server if any.
+///
+/// Data starting with a UTF-16 byte order mark is decoded as such. Feeds that are declared as
+/// ISO-8859-1 or are not valid UTF-8 are read as Latin-1, which maps every byte to the code point
+/// of the same value.
+fn decode_text(data: &[u8], charset: Option<&str>) -> String {
+    let latin1 = |data: &[u8]| data.iter().map(|&b| b as char).collect();
+    let utf16 = |data: &[u8], from_bytes: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|pair| from_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+    if let Some(data) = data.strip_prefix(b"\xff\xfe") {
+        return utf16(data, u16::from_le_bytes);
+    }
+    if let Some(data) = data.strip_prefix(b"\xfe\xff") {
+        return utf16(data, u16::from_be_bytes);
+    }
+    let data = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(data);
+    match charset {
+        Some(charset)
+            if charset.eq_ignore_ascii_case("iso-8859-1")
+                || charset.eq_ignore_ascii_case("latin1") =>
+        {
+            latin1(data)
+        }
+        _ => match std::str::from_utf8(data) {
+            Ok(text) => text.to_string(),
+            Err(_) => latin1(data),
+        },
+    }
+}
+
+/// Rewrite the `webcal://` and `webcals://` subscription URLs handed out by calendar apps to the
+/// `https://` URL they stand for, other URLs are returned as they are.
+fn resolve_webcal(url: &str) -> std::borrow::Cow<'_, str> {
+    for scheme in ["webcal://", "webcals://"] {
+        let matches = url
+            .get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme));
+        if matches {
+            return format!("https://{}", &url[scheme.len()..]).into();
+        }
+    }
+    url.into()
+}
+
+/// Read the lines of the next event up to and including its `END:VEVENT`, `None` if there are
+/// no more events.
+///
+/// The lines are kept as bytes, they are only checked to be UTF-8 once they are unfolded.
+#[cfg(feature = "stream")]
+async fn read_event_lines<R: tokio::io::AsyncBufRead + Unpin>(
+    reader: &mut R,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    use tokio::io::AsyncBufReadExt;
+
+    let mut line = vec![];
+    let mut lines: Option<Vec<u8>> = None;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            return match lines {
+                Some(_) => Err(io::Error::from(ErrorKind::UnexpectedEof).into()),
+                None => Ok(None),
+            };
+        }
+        let content = line.strip_suffix(b"\n").unwrap_or(&line);
+        let content = content.strip_suffix(b"\r").unwrap_or(content);
+        match lines.as_mut() {
+            None if content.eq_ignore_ascii_case(b"BEGIN:VEVENT") => lines = Some(vec![]),
+            None => (),
+            Some(lines) => {
+                lines.extend_from_slice(&line);
+                if content.eq_ignore_ascii_case(b"END:VEVENT") {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(lines)
+}
+
+/// The size up to which responses are decompressed by [`gunzip_unlabeled`], so a small gzip bomb
+/// can't exhaust the memory.
+#[cfg(feature = "gzip")]
+const MAX_DECOMPRESSED_SIZE: u64 = 128 * 1024 * 1024;
+
+/// Decompress a body which is gzip data although the server didn't say so in `Content-Encoding`,
+/// failing if it is larger than `limit` bytes.
+#[cfg(feature = "gzip")]
+fn gunzip_unlabeled(data: &[u8], limit: u64) -> anyhow::Result<std::borrow::Cow<'_, [u8]>> {
+    if !data.starts_with(&[0x1f, 0x8b]) {
+        return Ok(data.into());
+    }
+    let mut decoded = vec![];
+    flate2::read::GzDecoder::new(data)
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut decoded)
+        .context("Could not decompress response")?;
+    if decoded.len() as u64 > limit {
+        anyhow::bail!("Decompressed response is larger than {} bytes", limit);
+    }
+    Ok(decoded.into())
+}
+
+impl Calendar {
+    /// Request HTTP or HTTPS to iCalendar url, the `webcal://` and `webcals://` subscription URLs
+    /// handed out by calendar apps are fetched over HTTPS.
+    ///
+    /// The body is decoded according to the `charset` of the `Content-Type` header, see
+    /// [`Calendar::new_from_bytes`] for how undeclared encodings are handled.
+    ///
+    /// Bodies sent with `Content-Encoding: gzip` or `deflate` are decompressed transparently. With
+    /// the `gzip` feature so are gzip files served without that header, e.g. a `feed.ics.gz`, up
+    /// to 128 MiB.
+    ///
+    /// ```no_run
+    /// use web_ical::Calendar;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let ical = Calendar::new("webcal://example.com/feed.ics").await.unwrap();
+    /// println!("{} events", ical.events.len());
+    /// # }
+    /// ```
+    pub async fn new(url: &str) -> anyhow::Result<Calendar> {
+        Self::new_with_options(url, &FetchOptions::default()).await
+    }
+
+    /// Request an iCalendar url like [`Calendar::new`], as configured by `options`.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use web_ical::{Calendar, FetchOptions};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let options = FetchOptions {
+    ///     max_redirects: 3,
+    ///     timeout: Some(Duration::from_secs(30)),
+    /// };
+    /// let ical = Calendar::new_with_options("https://example.com/feed.ics", &options).await;
+    /// # }
+    /// ```
+    pub async fn new_with_options(url: &str, options: &FetchOptions) -> anyhow::Result<Calendar> {
+        match Self::fetch(url, options, None).await? {
+            CalendarFetch::Modified { calendar, .. } => Ok(*calendar),
+            CalendarFetch::NotModified => anyhow::bail!("unexpected 304 Not Modified"),
+        }
+    }
+
+    /// Request an iCalendar url like [`Calendar::new`], unless it is unchanged since it was
+    /// fetched with `etag`.
+    ///
+    /// `etag` is sent as `If-None-Match`, a `304 Not Modified` answer results in
+    /// [`CalendarFetch::NotModified`]. This spares polling clients from downloading a feed
+    /// again and again.
+    ///
+    /// ```no_run
+    /// use web_ical::{Calendar, CalendarFetch};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let url = "https://example.com/feed.ics";
+    /// let etag = match Calendar::new_conditional(url, None).await.unwrap() {
+    ///     CalendarFetch::Modified { calendar, etag } => {
+    ///         println!("{} events", calendar.events.len());
+    ///         etag
+    ///     }
+    ///     CalendarFetch::NotModified => None,
+    /// };
+    ///
+    /// // polling again later
+    /// match Calendar::new_conditional(url, etag.as_deref()).await.unwrap() {
+    ///     CalendarFetch::Modified { calendar, .. } => println!("{} events", calendar.events.len()),
+    ///     CalendarFetch::NotModified => println!("unchanged"),
+    /// }
+    /// # }
+    /// ```
+    pub async fn new_conditional(url: &str, etag: Option<&str>) -> anyhow::Result<CalendarFetch> {
+        Self::fetch(url, &FetchOptions::default(), etag).await
+    }
+
+    /// Request an iCalendar url, conditionally on it not matching `etag` if one is given.
+    async fn fetch(
+        url: &str,
+        options: &FetchOptions,
+        etag: Option<&str>,
+    ) -> anyhow::Result<CalendarFetch> {
+        let max_redirects = options.max_redirects;
+        let redirects = reqwest::redirect::Policy::custom(move |attempt| {
+            // the URLs requested so far include the original one
+            if attempt.previous().len() > max_redirects {
+                attempt.error("too many redirects")
+            } else {
+                attempt.follow()
+            }
+        });
+        let mut client = reqwest::Client::builder().redirect(redirects);
+        if let Some(timeout) = options.timeout {
+            client = client.timeout(timeout);
+        }
+        let client = client.build().context("Could not create HTTP client")?;
+        let mut request = client.get(resolve_webcal(url).as_ref());
+        if let Some(etag) = etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await.context("Could not make request")?;
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(CalendarFetch::NotModified);
+        }
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|val| val.to_str().ok())
+            .map(str::to_string);
+        let charset = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|val| val.to_str().ok())
+            .and_then(|val| {
+                val.split(';')
+                    .filter_map(|part| part.trim().split_once('='))
+                    .find(|(name, _)| name.eq_ignore_ascii_case("charset"))
+                    .map(|(_, charset)| charset.trim_matches('"').to_string())
+            });
+        let data = response.bytes().await.context("Could not read response")?;
+        #[cfg(feature = "gzip")]
+        let data = gunzip_unlabeled(&data, MAX_DECOMPRESSED_SIZE)?;
+        let calendar = Self::new_from_data(&decode_text(&data, charset.as_deref()))?;
+        Ok(CalendarFetch::Modified {
+            calendar: Box::new(calendar),
+            etag,
+        })
+    }
+
+    /// Parse every calendar of a feed which concatenates several `VCALENDAR` objects.
+    ///
+    /// [`Calendar::new_from_data`] only returns the first of them. Whitespace and byte order marks
+    /// between the calendars are ignored.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let data = "BEGIN:VCALENDAR\r\n\
+    ///             PRODID:-//First//EN\r\n\
+    ///             VERSION:2.0\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             UID:1@example.com\r\n\
+    ///             END:VEVENT\r\n\
+    ///             END:VCALENDAR\r\n\
+    ///             BEGIN:VCALENDAR\r\n\
+    ///             PRODID:-//Second//EN\r\n\
+    ///             VERSION:2.0\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             UID:2@example.com\r\n\
+    ///             END:VEVENT\r\n\
+    ///             END:VCALENDAR\r\n";
+    ///
+    /// let calendars = Calendar::parse_many(data).unwrap();
+    /// assert_eq!(calendars.len(), 2);
+    /// assert_eq!(calendars[0].prodid, "-//First//EN");
+    /// assert_eq!(calendars[1].prodid, "-//Second//EN");
+    /// assert_eq!(calendars[1].events[0].uid.as_deref(), Some("2@example.com"));
+    ///
+    /// assert_eq!(Calendar::new_from_data(data).unwrap().prodid, "-//First//EN");
+    ///
+    /// // byte order marks and blank lines between the calendars are skipped
+    /// let with_bom = data.replace("END:VCALENDAR\r\nBEGIN", "END:VCALENDAR\r\n\r\n\u{feff}BEGIN");
+    /// assert!(with_bom.contains("\r\n\r\n\u{feff}BEGIN:VCALENDAR"));
+    /// assert_eq!(Calendar::parse_many(&with_bom).unwrap().len(), 2);
+    /// ```
+    pub fn parse_many(data: &str) -> anyhow::Result<Vec<Calendar>> {
+        parse_many_cals(Cursor::new(data), &ParseOptions::default(), &mut vec![])
+    }
+
+    /// Create a `Calendar` from raw bytes, e.g. a file read from disk.
+    ///
+    /// Data starting with a byte order mark is read as UTF-8 or UTF-16 accordingly. Other data
+    /// that is not valid UTF-8 is assumed to be Latin-1 (ISO-8859-1), which old European feeds
+    /// still use.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let data = b"BEGIN:VCALENDAR\r\n\
+    ///              PRODID:-//Test//EN\r\n\
+    ///              VERSION:2.0\r\n\
+    ///              BEGIN:VEVENT\r\n\
+    ///              UID:1@example.com\r\n\
+    ///              SUMMARY:R\xe9union \xe0 Z\xfcrich\r\n\
+    ///              END:VEVENT\r\n\
+    ///              END:VCALENDAR\r\n";
+    /// let ical = Calendar::new_from_bytes(data).unwrap();
+    /// assert_eq!(ical.events[0].summary.as_deref(), Some("R\u{e9}union \u{e0} Z\u{fc}rich"));
+    ///
+    /// let utf8 = "BEGIN:VCALENDAR\r\n\
+    ///             PRODID:-//Test//EN\r\n\
+    ///             VERSION:2.0\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             UID:1@example.com\r\n\
+    ///             SUMMARY:R\u{e9}union\r\n\
+    ///             END:VEVENT\r\n\
+    ///             END:VCALENDAR\r\n";
+    /// let ical = Calendar::new_from_bytes(utf8.as_bytes()).unwrap();
+    /// assert_eq!(ical.events[0].summary.as_deref(), Some("R\u{e9}union"));
+    /// ```
+    pub fn new_from_bytes(data: &[u8]) -> anyhow::Result<Calendar> {
+        Self::new_from_data(&decode_text(data, None))
+    }
+
+    /// Create a `Calendar` from text in memory.
+    ///
+    /// Property and parameter names as well as component names are matched case-insensitively.
+    /// Text values such as `SUMMARY` and `DESCRIPTION` encoded with `ENCODING=QUOTED-PRINTABLE` by
+    /// old Outlook versions are decoded.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "begin:vcalendar\r\n\
+    ///      prodid:-//Test//EN\r\n\
+    ///      version:2.0\r\n\
+    ///      x-wr-calname:Team\r\n\
+    ///      begin:vevent\r\n\
+    ///      uid:1@example.com\r\n\
+    ///      dtstart;value=date:20190522\r\n\
+    ///      Summary:Offsite\r\n\
+    ///      begin:valarm\r\n\
+    ///      action:DISPLAY\r\n\
+    ///      trigger:-PT15M\r\n\
+    ///      end:valarm\r\n\
+    ///      end:vevent\r\n\
+    ///      end:vcalendar\r\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(ical.prodid, "-//Test//EN");
+    /// assert_eq!(ical.x_wr_calname.as_deref(), Some("Team"));
+    /// let event = &ical.events[0];
+    /// assert_eq!(event.summary.as_deref(), Some("Offsite"));
+    /// assert_eq!(event.dtstart, Some(Utc.with_ymd_and_hms(2019, 5, 22, 0, 0, 0).unwrap()));
+    /// assert!(event.all_day);
+    /// assert_eq!(event.alarms[0].action, "DISPLAY");
+    /// assert!(event.extra.is_empty());
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Microsoft Corporation//Outlook 11.0 MIMEDIR//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DESCRIPTION;ENCODING=QUOTED-PRINTABLE:R=C3=A9union d'=C3=A9quipe=3D=\r\n\
+    ///      =0D=0Aau caf=C3=A9\r\n\
+    ///      SUMMARY;CHARSET=ISO-8859-1;ENCODING=QUOTED-PRINTABLE:Caf=E9\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let event = &ical.events[0];
+    /// assert_eq!(
+    ///     event.description.as_deref(),
+    ///     Some("R\u{e9}union d'\u{e9}quipe=\nau caf\u{e9}")
+    /// );
+    /// assert_eq!(event.summary.as_deref(), Some("Caf\u{e9}"));
+    /// ```
+    pub fn new_from_data(data: &str) -> anyhow::Result<Calendar> {
+        parse_cal(Cursor::new(data), &ParseOptions::default(), &mut vec![])
+    }
+
+    /// Parse only the properties of a calendar, e.g. its name and `PRODID`, to identify a feed
+    /// without going through all of its events.
+    ///
+    /// Parsing stops at the first `BEGIN:VEVENT`, so the returned calendar has no events and
+    /// only the components found before them.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let data = "BEGIN:VCALENDAR\r\n\
+    ///             PRODID:-//Test//EN\r\n\
+    ///             VERSION:2.0\r\n\
+    ///             X-WR-CALNAME:Holidays\r\n\
+    ///             X-WR-TIMEZONE:Europe/Berlin\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             UID:1@example.com\r\n\
+    ///             END:VEVENT\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             this event is never looked at\r\n";
+    /// let ical = Calendar::parse_headers_only(data).unwrap();
+    /// assert_eq!(ical.prodid, "-//Test//EN");
+    /// assert_eq!(ical.version, "2.0");
+    /// assert_eq!(ical.x_wr_calname.as_deref(), Some("Holidays"));
+    /// assert_eq!(ical.x_wr_timezone.as_deref(), Some("Europe/Berlin"));
+    /// assert!(ical.events.is_empty());
+    /// ```
+    pub fn parse_headers_only(data: &str) -> anyhow::Result<Calendar> {
+        let mut raw = LineReader::new(Cursor::new(data));
+        read_begin_vcalendar(&mut raw)?;
+        parse_vcalendar(&mut raw, &ParseOptions::default(), true, &mut vec![])
+    }
+
+    /// Create a `Calendar` from text in memory, also returning the warnings collected while
+    /// parsing it, such as unknown properties or malformed values that were skipped.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let data = "BEGIN:VCALENDAR\r\n\
+    ///             PRODID:-//Test//EN\r\n\
+    ///             VERSION:2.0\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             UID:1@example.com\r\n\
+    ///             X-FOO:bar\r\n\
+    ///             END:VEVENT\r\n\
+    ///             END:VCALENDAR\r\n";
+    /// let (ical, warnings) = Calendar::parse_verbose(data);
+    /// assert_eq!(ical.unwrap().events[0].extra, vec![("X-FOO".to_string(), "bar".to_string())]);
+    /// assert_eq!(warnings.len(), 1);
+    /// assert!(warnings[0].message.contains("X-FOO"));
+    /// ```
+    pub fn parse_verbose(data: &str) -> (anyhow::Result<Calendar>, Vec<Warning>) {
+        let mut warnings = vec![];
+        let calendar = parse_cal(Cursor::new(data), &ParseOptions::default(), &mut warnings);
+        (calendar, warnings)
+    }
+
+    /// Create a `Calendar` from text in memory, also returning every physical line read with
+    /// whether it was recognized, folded into the previous one or skipped.
+    ///
+    /// This helps to find out why a feed comes out empty. The lines are only recorded here, the
+    /// other constructors don't pay for it.
+    ///
+    /// ```
+    /// use web_ical::{Calendar, LineStatus};
+    ///
+    /// let data = "BEGIN:VCALENDAR\r\n\
+    ///             PRODID:-//Test//EN\r\n\
+    ///             VERSION:2.0\r\n\
+    ///             BEGIN:VTODO\r\n\
+    ///             SUMMARY:Buy milk\r\n\
+    ///             END:VTODO\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             UID:1@example.com\r\n\
+    ///             SUMMARY:Team\r\n\
+    ///             \x20 meeting\r\n\
+    ///             not a property\r\n\
+    ///             END:VEVENT\r\n\
+    ///             END:VCALENDAR\r\n";
+    /// let (ical, lines) = Calendar::parse_debug(data);
+    /// assert_eq!(ical.unwrap().events[0].summary.as_deref(), Some("Team meeting"));
+    /// assert_eq!(lines.len(), 13);
+    /// assert_eq!(lines[4].number, 5);
+    /// assert_eq!(lines[4].text, "SUMMARY:Buy milk");
+    /// let statuses: Vec<_> = lines.iter().map(|line| line.status).collect();
+    /// assert_eq!(
+    ///     statuses[3..11],
+    ///     [
+    ///         LineStatus::Skipped,
+    ///         LineStatus::Skipped,
+    ///         LineStatus::Skipped,
+    ///         LineStatus::Recognized,
+    ///         LineStatus::Recognized,
+    ///         LineStatus::Recognized,
+    ///         LineStatus::Folded,
+    ///         LineStatus::Skipped,
+    ///     ]
+    /// );
+    /// ```
+    pub fn parse_debug(data: &str) -> (anyhow::Result<Calendar>, Vec<RawLine>) {
+        let mut raw = LineReader::recording(Cursor::new(data));
+        let calendar = read_begin_vcalendar(&mut raw)
+            .and_then(|()| parse_vcalendar(&mut raw, &ParseOptions::default(), false, &mut vec![]));
+        (calendar, raw.into_lines())
+    }
+
+    /// Create a `Calendar` from text in memory, parsing it as configured by `options`.
+    pub fn new_from_data_with_options(
+        data: &str,
+        options: &ParseOptions,
+    ) -> anyhow::Result<Calendar> {
+        parse_cal(Cursor::new(data), options, &mut vec![])
+    }
+
+    /// Create a `Calendar` by parsing it line by line from a reader, e.g. a file or a network
+    /// stream, without reading it into memory first.
+    ///
+    /// ```
+    /// use std::io::{BufReader, Write};
+    /// use web_ical::Calendar;
+    ///
+    /// let path = std::env::temp_dir().join("web_ical_from_reader.ics");
+    /// let mut file = std::fs::File::create(&path).unwrap();
+    /// write!(
+    ///     file,
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      SUMMARY:A summary which is long enough that it has to be folded onto a \r\n \
+    ///      second line\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n"
+    /// )
+    /// .unwrap();
+    /// drop(file);
+    ///
+    /// let reader = BufReader::new(std::fs::File::open(&path).unwrap());
+    /// let ical = Calendar::from_reader(reader).unwrap();
+    /// assert_eq!(
+    ///     ical.events[0].summary.as_deref(),
+    ///     Some("A summary which is long enough that it has to be folded onto a second line")
+    /// );
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn from_reader<R: BufRead>(reader: R) -> anyhow::Result<Calendar> {
+        parse_cal(reader, &ParseOptions::default(), &mut vec![])
+    }
+
+    /// Create a `Calendar` from a local `.ics` file, the counterpart of [`Calendar::export_ics`].
+    ///
+    /// The file is parsed while it is read, see [`Calendar::from_reader`]. It must be UTF-8, a
+    /// leading byte order mark is skipped.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let path = std::env::temp_dir().join("web_ical_from_file.ics");
+    /// std::fs::write(
+    ///     &path,
+    ///     "\u{feff}BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      SUMMARY:Sync\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let ical = Calendar::from_file(&path).unwrap();
+    /// assert_eq!(ical.prodid, "-//Test//EN");
+    /// assert_eq!(ical.events[0].summary.as_deref(), Some("Sync"));
+    /// std::fs::remove_file(&path).unwrap();
+    ///
+    /// assert!(Calendar::from_file(&path).is_err());
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Calendar> {
+        let file = File::open(path).context("Could not open file")?;
+        let mut reader = BufReader::new(file);
+        if reader.fill_buf()?.starts_with(b"\xef\xbb\xbf") {
+            reader.consume(3);
+        }
+        parse_cal(reader, &ParseOptions::default(), &mut vec![])
+    }
+
+    /// Create a `Calendar` from a file mapped read-only into memory, without copying it first.
+    ///
+    /// This is meant for huge local archives, which must not be modified while they are being
+    /// parsed. The encoding is detected like [`Calendar::new_from_bytes`] does. Only UTF-8 files
+    /// are parsed in place though, UTF-16 and Latin-1 ones are decoded into a copy first.
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use web_ical::Calendar;
+    ///
+    /// let path = std::env::temp_dir().join("web_ical_from_mmap.ics");
+    /// let mut file = std::io::BufWriter::new(std::fs::File::create(&path).unwrap());
+    /// write!(file, "\u{feff}BEGIN:VCALENDAR\r\nPRODID:-//Test//EN\r\nVERSION:2.0\r\n").unwrap();
+    /// for i in 0..10_000 {
+    ///     write!(
+    ///         file,
+    ///         "BEGIN:VEVENT\r\nUID:{}@example.com\r\nDTSTART:20190522T090000Z\r\nEND:VEVENT\r\n",
+    ///         i
+    ///     )
+    ///     .unwrap();
+    /// }
+    /// write!(file, "END:VCALENDAR\r\n").unwrap();
+    /// drop(file);
+    ///
+    /// let ical = Calendar::from_mmap(&path).unwrap();
+    /// assert_eq!(ical.events.len(), 10_000);
+    /// assert_eq!(ical.events[9_999].uid.as_deref(), Some("9999@example.com"));
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    #[cfg(feature = "mmap")]
+    pub fn from_mmap<P: AsRef<Path>>(path: P) -> anyhow::Result<Calendar> {
+        let file = File::open(path).context("Could not open file")?;
+        // SAFETY: the map is read-only and the caller guarantees that the file stays unmodified
+        let map = unsafe { memmap2::Mmap::map(&file) }.context("Could not map file")?;
+        let bytes = map.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&map);
+        let data = match std::str::from_utf8(bytes) {
+            Ok(data) => std::borrow::Cow::Borrowed(data),
+            Err(_) => std::borrow::Cow::Owned(decode_text(bytes, None)),
+        };
+        parse_cal(Cursor::new(&*data), &ParseOptions::default(), &mut vec![])
+    }
+
+    /// Parse the events of a calendar one after the other while reading it, without keeping
+    /// them all in memory.
+    ///
+    /// Only the events are yielded, [`Calendar::parse_headers_only`] reads the properties of the
+    /// calendar. The stream ends after the first error.
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use futures::StreamExt;
+    /// use web_ical::Calendar;
+    ///
+    /// let mut data = String::from("BEGIN:VCALENDAR\r\nPRODID:-//Test//EN\r\nVERSION:2.0\r\n");
+    /// for i in 0..3 {
+    ///     data.push_str(&format!(
+    ///         "BEGIN:VEVENT\r\n\
+    ///          UID:{}@example.com\r\n\
+    ///          SUMMARY:A summary which is long enough that it has to be folded onto a \r\n \
+    ///          second line\r\n\
+    ///          END:VEVENT\r\n",
+    ///         i
+    ///     ));
+    /// }
+    /// data.push_str("END:VCALENDAR\r\n");
+    ///
+    /// let events = Calendar::events_stream(data.as_bytes());
+    /// let uids: Vec<String> = events
+    ///     .map(|event| event.unwrap().uid.unwrap())
+    ///     .collect()
+    ///     .await;
+    /// assert_eq!(uids, ["0@example.com", "1@example.com", "2@example.com"]);
+    ///
+    /// // a truncated event is an error
+    /// let truncated = &data[..data.find("END:VEVENT").unwrap()];
+    /// let results: Vec<_> = Calendar::events_stream(truncated.as_bytes()).collect().await;
+    /// assert_eq!(results.len(), 1);
+    /// assert!(results[0].is_err());
+    /// # }
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn events_stream<R: tokio::io::AsyncBufRead + Unpin>(
+        reader: R,
+    ) -> impl futures::Stream<Item = anyhow::Result<Event>> {
+        futures::stream::unfold(Some(reader), |reader| async move {
+            let mut reader = reader?;
+            match read_event_lines(&mut reader).await {
+                Ok(Some(lines)) => {
+                    let options = ParseOptions::default();
+                    let event = parse_event(
+                        &mut LineReader::new(Cursor::new(lines)),
+                        &options,
+                        &mut vec![],
+                    );
+                    Some((event, Some(reader)))
+                }
+                Ok(None) => None,
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+
+    /// Check whether any event starts or ends at a floating time.
+    ///
+    /// Floating times carry neither a `Z` suffix nor a `TZID`, so the instant they refer to
+    /// depends on the zone of whoever reads the feed.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let floating = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1\r\n\
+    ///      DTSTART:20190522T090000\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert!(floating.has_floating_times());
+    ///
+    /// let utc = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTEND;TZID=Europe/Berlin:20190522T110000\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert!(!utc.has_floating_times());
+    /// assert!(utc.events[0].dtstart.is_some());
+    /// ```
+    pub fn has_floating_times(&self) -> bool {
+        self.events.iter().any(|ev| ev.floating)
+    }
+
+    /// The first day of the week, taken from the `WKST` of the first recurrence rule that has
+    /// one and defaulting to Monday like RFC 5545 does.
+    ///
+    /// ```
+    /// use chrono::Weekday;
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      RRULE:FREQ=WEEKLY;WKST=SU\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(ical.week_start(), Weekday::Sun);
+    ///
+    /// let empty = Calendar::create("-//Test//EN", "2.0", "GREGORIAN", "PUBLISH", "", "UTC");
+    /// assert_eq!(empty.week_start(), Weekday::Mon);
+    /// ```
+    pub fn week_start(&self) -> Weekday {
+        self.events
+            .iter()
+            .filter_map(|ev| ev.repeat.as_ref()?.wkst.as_deref())
+            .find_map(parse_weekday)
+            .unwrap_or(Weekday::Mon)
+    }
+
+    /// Create an empty iCalendar with the given calendar properties.
+    ///
+    /// An empty `prodid` is replaced by [`DEFAULT_PRODID`], the optional properties are left out
+    /// when given as empty strings.
+    ///
+    /// # Create an iCalendar
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::create(
+    ///                       "-//My Business Inc//My Calendar 70.9054//EN",
+    ///                       "2.0",
+    ///                       "GREGORIAN",
+    ///                       "PUBLISH",
+    ///                       "example@gmail.com",
+    ///                       "America/New_York");
+    ///
+    /// let mut out = Vec::new();
+    /// ical.export_to(&mut out).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(out.starts_with("BEGIN:VCALENDAR\r\nPRODID:-//My Business Inc//My Calendar 70.9054//EN\r\n"));
+    /// assert!(out.contains("X-WR-TIMEZONE:America/New_York\r\n"));
+    /// ```
+    pub fn create(
+        prodid: &str,
+        version: &str,
+        calscale: &str,
+        method: &str,
+        x_wr_calname: &str,
+        x_wr_timezone: &str,
+    ) -> Calendar {
+        let given = |value: &str| Some(value.to_string()).filter(|value| !value.is_empty());
+        Calendar {
+            name: None,
+            color: None,
+            refresh_interval: None,
+            prodid: if prodid.is_empty() {
+                DEFAULT_PRODID.to_string()
+            } else {
+                prodid.to_string()
+            },
+            version: version.to_string(),
+            calscale: given(calscale),
+            method: given(method).map(|method| Method::from(method.as_str())),
+            x_wr_calname: given(x_wr_calname),
+            x_wr_timezone: given(x_wr_timezone),
+            categories: vec![],
+            timezones: vec![],
+            events: vec![],
+            journals: vec![],
+            freebusy: vec![],
+            extra: vec![],
+            property_order: vec![],
+        }
+    }
+
+    /// Replace the `PRODID`, e.g. so that a tool re-exporting a parsed calendar identifies
+    /// itself as RFC 5545 asks for.
+    ///
+    /// ```
+    /// use web_ical::{Calendar, DEFAULT_PRODID};
+    ///
+    /// let mut ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\nPRODID:-//Upstream//EN\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// ical.set_prodid("-//My Tool 1.0//EN");
+    /// assert!(ical.to_ics_string().starts_with("BEGIN:VCALENDAR\r\nPRODID:-//My Tool 1.0//EN\r\n"));
+    ///
+    /// let ical = Calendar::create("", "2.0", "GREGORIAN", "PUBLISH", "", "UTC");
+    /// assert_eq!(ical.prodid, DEFAULT_PRODID);
+    /// assert!(ical.to_ics_string().contains("\r\nPRODID:-//web_ical//EN\r\n"));
+    /// ```
+    pub fn set_prodid(&mut self, prodid: impl Into<String>) {
+        self.prodid = prodid.into();
+    }
+
+    /// Add events to the calendar.
+    ///
+    /// # Add events
+    /// ```
+    /// use chrono::{DateTime, Duration, Utc};
+    /// use web_ical::{Calendar, Event, EventStatus, TimeTransparency};
+    ///
+    /// let date_tz: DateTime<Utc> = Utc::now();
+    /// let start_cal = date_tz + Duration::days(2);
+    ///
+    /// let mut own_event = Event::empty();
+    /// own_event.dtstart = Some(start_cal);
+    /// own_event.dtend = Some(start_cal);
+    /// own_event.dtstamp = Some(date_tz);
+    /// own_event.uid = Some("786566jhjh5546@google.com".to_string());
+    /// own_event.created = Some(date_tz);
+    /// own_event.description = Some("The description".to_string());
+    /// own_event.last_modified = Some(date_tz);
+    /// own_event.location = Some("Homestead FL".to_string());
+    /// own_event.sequence = Some(0);
+    /// own_event.status = Some(EventStatus::Confirmed);
+    /// own_event.summary = Some("My business (Not available)".to_string());
+    /// own_event.transp = TimeTransparency::Opaque;
+    ///
+    /// let mut ical =  Calendar::create(
+    ///                       "-//My Business Inc//My Calendar 70.9054//EN",
+    ///                       "2.0",
+    ///                       "GREGORIAN",
+    ///                       "PUBLISH",
+    ///                       "example@gmail.com",
+    ///                       "America/New_York");
+    ///
+    /// ical.add_event(own_event);
+    /// println!("{}", ical.events[0].summary.as_ref().unwrap());
+    ///
+    /// ical.export_to(&mut std::io::stdout()).unwrap();
+    /// ```
+    pub fn add_event(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    /// The first event with the given UID.
+    ///
+    /// UIDs should be unique, but recurring events whose instances are overridden and merged
+    /// feeds may share one, in which case the first event in [`Calendar::events`] is returned.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let mut ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      SUMMARY:Lunch\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:2@example.com\r\n\
+    ///      SUMMARY:Dinner\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let event = ical.find_event("2@example.com").unwrap();
+    /// assert_eq!(event.summary.as_deref(), Some("Dinner"));
+    /// assert!(ical.find_event("3@example.com").is_none());
+    ///
+    /// ical.find_event_mut("1@example.com").unwrap().summary = Some("Brunch".to_string());
+    /// assert_eq!(ical.events[0].summary.as_deref(), Some("Brunch"));
+    ///
+    /// let removed = ical.remove_event("1@example.com").unwrap();
+    /// assert_eq!(removed.summary.as_deref(), Some("Brunch"));
+    /// assert_eq!(ical.events.len(), 1);
+    /// assert!(ical.remove_event("1@example.com").is_none());
+    /// ```
+    pub fn find_event(&self, uid: &str) -> Option<&Event> {
+        self.events.iter().find(|ev| ev.uid.as_deref() == Some(uid))
+    }
+
+    /// The first event with the given UID for editing, see [`Calendar::find_event`].
+    pub fn find_event_mut(&mut self, uid: &str) -> Option<&mut Event> {
+        self.events
+            .iter_mut()
+            .find(|ev| ev.uid.as_deref() == Some(uid))
+    }
+
+    /// Remove the first event with the given UID and return it, see [`Calendar::find_event`].
+    pub fn remove_event(&mut self, uid: &str) -> Option<Event> {
+        let index = self
+            .events
+            .iter()
+            .position(|ev| ev.uid.as_deref() == Some(uid))?;
+        Some(self.events.remove(index))
+    }
+
+    /// Replace the event with the same UID and recurrence id, or add it if there is none.
+    ///
+    /// Returns whether an event was replaced. The sequence number is bumped when it equals the
+    /// one of the replaced event, so clients notice the change.
+    ///
+    /// ```
+    /// use web_ical::{Calendar, Event};
+    ///
+    /// let mut ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      SEQUENCE:2\r\n\
+    ///      SUMMARY:Lunch\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let mut edited = ical.events[0].clone();
+    /// edited.summary = Some("Late lunch".to_string());
+    /// assert!(ical.replace_event(edited));
+    /// assert_eq!(ical.events.len(), 1);
+    /// assert_eq!(ical.events[0].summary.as_deref(), Some("Late lunch"));
+    /// assert_eq!(ical.events[0].sequence, Some(3));
+    ///
+    /// let mut new_event = Event::empty();
+    /// new_event.uid = Some("2@example.com".to_string());
+    /// assert!(!ical.replace_event(new_event));
+    /// assert_eq!(ical.events.len(), 2);
+    /// ```
+    pub fn replace_event(&mut self, mut event: Event) -> bool {
+        let existing = self
+            .events
+            .iter_mut()
+            .find(|ev| ev.uid == event.uid && ev.recur_id == event.recur_id);
+        match existing {
+            Some(old) => {
+                if event.sequence == old.sequence {
+                    event.sequence = Some(old.sequence.unwrap_or(0) + 1);
+                }
+                *old = event;
+                true
+            }
+            None => {
+                self.events.push(event);
+                false
+            }
+        }
+    }
+
+    /// Combine the components of `other` into this calendar, keeping this calendar's properties.
+    ///
+    /// Timezone definitions are deduplicated by TZID, keeping the more complete definition, while
+    /// events, journals and free/busy components are all appended, see [`Calendar::merge_dedup`]
+    /// to skip events which are already present.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let mut first = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//First//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VTIMEZONE\r\n\
+    ///      TZID:Europe/Berlin\r\n\
+    ///      BEGIN:STANDARD\r\n\
+    ///      DTSTART:19701025T030000\r\n\
+    ///      TZOFFSETFROM:+0200\r\n\
+    ///      TZOFFSETTO:+0100\r\n\
+    ///      END:STANDARD\r\n\
+    ///      END:VTIMEZONE\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let second = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Second//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VTIMEZONE\r\n\
+    ///      TZID:Europe/Berlin\r\n\
+    ///      BEGIN:STANDARD\r\n\
+    ///      DTSTART:19701025T030000\r\n\
+    ///      TZOFFSETFROM:+0200\r\n\
+    ///      TZOFFSETTO:+0100\r\n\
+    ///      END:STANDARD\r\n\
+    ///      BEGIN:DAYLIGHT\r\n\
+    ///      DTSTART:19700329T020000\r\n\
+    ///      TZOFFSETFROM:+0100\r\n\
+    ///      TZOFFSETTO:+0200\r\n\
+    ///      END:DAYLIGHT\r\n\
+    ///      END:VTIMEZONE\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// first.merge(second);
+    /// assert_eq!(first.prodid, "-//First//EN");
+    /// assert_eq!(first.timezones.len(), 1);
+    /// assert!(first.timezones[0].lines.contains(&"BEGIN:DAYLIGHT".to_string()));
+    ///
+    /// let mut out = Vec::new();
+    /// first.export_to(&mut out).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert_eq!(out.matches("BEGIN:VTIMEZONE").count(), 1);
+    /// ```
+    pub fn merge(&mut self, other: Calendar) {
+        for tz in other.timezones {
+            match self.timezones.iter_mut().find(|own| own.tzid == tz.tzid) {
+                Some(own) => {
+                    if tz.lines.len() > own.lines.len() {
+                        *own = tz;
+                    }
+                }
+                None => self.timezones.push(tz),
+            }
+        }
+        self.events.extend(other.events);
+        self.journals.extend(other.journals);
+        self.freebusy.extend(other.freebusy);
+    }
+
+    /// Like [`Calendar::merge`], but an event whose UID and recurrence id are already present
+    /// only replaces the existing one if it has a higher `sequence`, a missing one counting as
+    /// `0`. Events without a UID are always added.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let feed = |prodid: &str, sequence: u32, summary: &str| {
+    ///     Calendar::new_from_data(&format!(
+    ///         "BEGIN:VCALENDAR\r\n\
+    ///          PRODID:{}\r\n\
+    ///          VERSION:2.0\r\n\
+    ///          BEGIN:VEVENT\r\n\
+    ///          UID:shared@example.com\r\n\
+    ///          SEQUENCE:{}\r\n\
+    ///          SUMMARY:{}\r\n\
+    ///          END:VEVENT\r\n\
+    ///          BEGIN:VEVENT\r\n\
+    ///          UID:{}@example.com\r\n\
+    ///          END:VEVENT\r\n\
+    ///          END:VCALENDAR\r\n",
+    ///         prodid, sequence, summary, prodid
+    ///     ))
+    ///     .unwrap()
+    /// };
+    ///
+    /// let mut combined = feed("first", 1, "Old");
+    /// combined.merge_dedup(feed("second", 2, "New"));
+    /// combined.merge_dedup(feed("third", 0, "Stale"));
+    /// assert_eq!(combined.prodid, "first");
+    /// assert_eq!(combined.events.len(), 4);
+    /// assert_eq!(combined.events[0].summary.as_deref(), Some("New"));
+    /// assert_eq!(combined.events[0].sequence, Some(2));
+    /// ```
+    pub fn merge_dedup(&mut self, mut other: Calendar) {
+        let events = std::mem::take(&mut other.events);
+        self.merge(other);
+        for event in events {
+            let existing = self.events.iter_mut().find(|own| {
+                event.uid.is_some() && own.uid == event.uid && own.recur_id == event.recur_id
+            });
+            match existing {
+                Some(own) => {
+                    if event.sequence.unwrap_or(0) > own.sequence.unwrap_or(0) {
+                        *own = event;
+                    }
+                }
+                None => self.events.push(event),
+            }
+        }
+    }
+
+    /// Add a `DISPLAY` alarm to every event whose `PRIORITY` matches one of the rules, going off
+    /// the given time before the event starts, e.g. `(1, Duration::hours(1))` for a reminder one
+    /// hour before high priority events.
+    ///
+    /// Events which already have the same reminder are left alone.
+    ///
+    /// ```
+    /// use chrono::Duration;
+    /// use web_ical::{Calendar, Trigger};
+    ///
+    /// let mut ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:urgent@example.com\r\n\
+    ///      SUMMARY:Deadline\r\n\
+    ///      PRIORITY:1\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:normal@example.com\r\n\
+    ///      PRIORITY:5\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:unset@example.com\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let rules = [(1, Duration::hours(1)), (2, Duration::minutes(30))];
+    /// ical.add_default_reminders(&rules);
+    /// ical.add_default_reminders(&rules);
+    ///
+    /// let alarms = &ical.events[0].alarms;
+    /// assert_eq!(alarms.len(), 1);
+    /// assert_eq!(alarms[0].action, "DISPLAY");
+    /// assert_eq!(alarms[0].trigger, Some(Trigger::Relative(Duration::hours(-1))));
+    /// assert_eq!(alarms[0].description.as_deref(), Some("Deadline"));
+    /// assert!(ical.events[1].alarms.is_empty());
+    /// assert!(ical.events[2].alarms.is_empty());
+    /// ```
+    pub fn add_default_reminders(&mut self, by_priority: &[(u8, Duration)]) {
+        for ev in &mut self.events {
+            let priority = match ev.priority {
+                Some(priority) => priority,
+                None => continue,
+            };
+            for (_, before) in by_priority.iter().filter(|(rule, _)| *rule == priority) {
+                let alarm = Alarm {
+                    action: "DISPLAY".to_string(),
+                    trigger: Some(Trigger::Relative(-*before)),
+                    description: Some(ev.summary.clone().unwrap_or_else(|| "Reminder".to_string())),
+                };
+                if !ev.alarms.contains(&alarm) {
+                    ev.alarms.push(alarm);
+                }
+            }
+        }
+    }
+
+    /// Sort the events chronologically by `dtstart`, events without a start go last and keep
+    /// their order.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let mut ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:undated@example.com\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:late@example.com\r\n\
+    ///      DTSTART:20190601T090000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:early@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let uids = |events: &[web_ical::Event]| -> Vec<String> {
+    ///     events.iter().map(|ev| ev.uid.clone().unwrap()).collect()
+    /// };
+    /// let sorted = ical.sorted_events();
+    /// assert_eq!(
+    ///     uids(&sorted),
+    ///     ["early@example.com", "late@example.com", "undated@example.com"]
+    /// );
+    /// assert_eq!(ical.events[0].uid.as_deref(), Some("undated@example.com"));
+    ///
+    /// ical.sort_by_start();
+    /// assert_eq!(uids(&ical.events), uids(&sorted));
+    /// ```
+    pub fn sort_by_start(&mut self) {
+        self.events
+            .sort_by_key(|ev| (ev.dtstart.is_none(), ev.dtstart));
+    }
+
+    /// A copy of the events sorted like [`Calendar::sort_by_start`] does.
+    pub fn sorted_events(&self) -> Vec<Event> {
+        let mut events = self.events.clone();
+        events.sort_by_key(|ev| (ev.dtstart.is_none(), ev.dtstart));
+        events
+    }
+
+    /// The number of events, i.e. `VEVENT` components.
+    pub fn event_count(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether the calendar has neither events nor any other components with content, i.e.
+    /// journals or free/busy information. Time zones don't count, they only serve the others.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let parse = |components: &str| {
+    ///     Calendar::new_from_data(&format!(
+    ///         "BEGIN:VCALENDAR\r\n\
+    ///          PRODID:-//Test//EN\r\n\
+    ///          VERSION:2.0\r\n\
+    ///          {}\
+    ///          END:VCALENDAR\r\n",
+    ///         components
+    ///     ))
+    ///     .unwrap()
+    /// };
+    ///
+    /// let empty = parse("BEGIN:VTIMEZONE\r\nTZID:Europe/Berlin\r\nEND:VTIMEZONE\r\n");
+    /// assert_eq!(empty.event_count(), 0);
+    /// assert!(empty.is_empty());
+    ///
+    /// let journal = parse("BEGIN:VJOURNAL\r\nUID:1@example.com\r\nEND:VJOURNAL\r\n");
+    /// assert_eq!(journal.event_count(), 0);
+    /// assert!(!journal.is_empty());
+    ///
+    /// let events = parse(
+    ///     "BEGIN:VEVENT\r\nUID:1@example.com\r\nEND:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\nUID:2@example.com\r\nEND:VEVENT\r\n",
+    /// );
+    /// assert_eq!(events.event_count(), 2);
+    /// assert!(!events.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.journals.is_empty() && self.freebusy.is_empty()
+    }
+
+    /// The events which take whole days according to [`Event::is_all_day`].
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:holiday@example.com\r\n\
+    ///      DTSTART;VALUE=DATE:20190522\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:sync@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTEND:20190522T100000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:trip@example.com\r\n\
+    ///      DTSTART:20190523T000000Z\r\n\
+    ///      DTEND:20190525T000000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:unscheduled@example.com\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let uids = |events: Vec<&web_ical::Event>| -> Vec<String> {
+    ///     events.iter().filter_map(|ev| ev.uid.clone()).collect()
+    /// };
+    /// assert_eq!(
+    ///     uids(ical.all_day_events().collect()),
+    ///     ["holiday@example.com", "trip@example.com"]
+    /// );
+    /// assert_eq!(
+    ///     uids(ical.timed_events().collect()),
+    ///     ["sync@example.com", "unscheduled@example.com"]
+    /// );
+    /// ```
+    pub fn all_day_events(&self) -> impl Iterator<Item = &Event> {
+        self.events
+            .iter()
+            .filter(|ev| ev.is_all_day() == Some(true))
+    }
+
+    /// The events which don't take whole days, including those whose length is unknown.
+    pub fn timed_events(&self) -> impl Iterator<Item = &Event> {
+        self.events
+            .iter()
+            .filter(|ev| ev.is_all_day() != Some(true))
+    }
+
+    /// The indices into [`Calendar::events`] of every pair of events which overlap according
+    /// to [`Event::overlaps`], the smaller index first.
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTEND:20190522T100000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:2@example.com\r\n\
+    ///      DTSTART:20190522T100000Z\r\n\
+    ///      DTEND:20190522T110000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:3@example.com\r\n\
+    ///      DTSTART;VALUE=DATE:20190522\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:4@example.com\r\n\
+    ///      DTSTART:20190524T090000Z\r\n\
+    ///      DTEND:20190524T100000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(ical.conflicts(), [(0, 2), (1, 2)]);
+    /// ```
+    pub fn conflicts(&self) -> Vec<(usize, usize)> {
+        let mut conflicts = vec![];
+        for (i, event) in self.events.iter().enumerate() {
+            for (j, other) in self.events.iter().enumerate().skip(i + 1) {
+                if event.overlaps(other) {
+                    conflicts.push((i, j));
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// The occurrences of all events overlapping the window from `start` (inclusive) to `end`
+    /// (exclusive), e.g. to render a week view.
+    ///
+    /// Recurring events are expanded, every occurrence is returned as a copy of its event with
+    /// `dtstart` and `dtend` moved to the occurrence and no recurrence of its own. An occurrence
+    /// ends at `DTEND` or after `DURATION`, one without either only overlaps if it starts within
+    /// the window. Occurrences replaced by an event with a matching `UID` and
+    /// [`Event::recur_id`] are left out in favour of that event.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:weekly@example.com\r\n\
+    ///      DTSTART:20190506T090000Z\r\n\
+    ///      DURATION:PT1H\r\n\
+    ///      RRULE:FREQ=WEEKLY;BYDAY=MO,TH\r\n\
+    ///      EXDATE:20190516T090000Z\r\n\
+    ///      RDATE:20190518T090000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:once@example.com\r\n\
+    ///      DTSTART:20190601T090000Z\r\n\
+    ///      DTEND:20190601T100000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let start = Utc.with_ymd_and_hms(2019, 5, 13, 0, 0, 0).unwrap();
+    /// let end = Utc.with_ymd_and_hms(2019, 5, 27, 0, 0, 0).unwrap();
+    /// let starts: Vec<_> = ical
+    ///     .events_between(start, end)
+    ///     .iter()
+    ///     .map(|ev| ev.dtstart.unwrap())
+    ///     .collect();
+    /// assert_eq!(
+    ///     starts,
+    ///     [
+    ///         Utc.with_ymd_and_hms(2019, 5, 13, 9, 0, 0).unwrap(),
+    ///         Utc.with_ymd_and_hms(2019, 5, 18, 9, 0, 0).unwrap(),
+    ///         Utc.with_ymd_and_hms(2019, 5, 20, 9, 0, 0).unwrap(),
+    ///         Utc.with_ymd_and_hms(2019, 5, 23, 9, 0, 0).unwrap(),
+    ///     ]
+    /// );
+    ///
+    /// // an occurrence which started before the window still overlaps it
+    /// let start = Utc.with_ymd_and_hms(2019, 6, 1, 9, 30, 0).unwrap();
+    /// let end = Utc.with_ymd_and_hms(2019, 6, 2, 0, 0, 0).unwrap();
+    /// let events = ical.events_between(start, end);
+    /// assert_eq!(events.len(), 1);
+    /// assert_eq!(events[0].uid.as_deref(), Some("once@example.com"));
+    /// ```
+    pub fn events_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<Event> {
+        let overridden: Vec<(&Option<String>, DateTime<Utc>)> = self
+            .events
+            .iter()
+            .filter_map(|ev| Some((&ev.uid, ev.recur_id?)))
+            .collect();
+        let mut found = vec![];
+        for ev in &self.events {
+            let length = ev.length();
+            for occurrence in ev.occurrences() {
+                if occurrence >= end {
+                    break;
+                }
+                if ev.recur_id.is_none() && overridden.contains(&(&ev.uid, occurrence)) {
+                    continue;
+                }
+                if end_after(occurrence, length) <= start
+                    && (length > Duration::zero() || occurrence < start)
+                {
+                    continue;
+                }
+                let mut instance = ev.clone();
+                instance.dtstart = Some(occurrence);
+                if ev.dtend.is_some() {
+                    instance.dtend = Some(end_after(occurrence, length));
+                }
+                instance.repeat = None;
+                instance.rdates.clear();
+                instance.exdates.clear();
+                found.push(instance);
+            }
+        }
+        found.sort_by_key(|ev| ev.dtstart);
+        found
+    }
+
+    /// Check that the calendar and its events have the properties RFC 5545 requires, e.g. before
+    /// exporting a calendar that was assembled by hand.
+    ///
+    /// Events need a `DTSTAMP` and `UID`, and a `DTSTART` unless the calendar has a `METHOD` and
+    /// the event has no `DTEND`.
+    ///
+    /// ```
+    /// use chrono::Utc;
+    /// use web_ical::{Calendar, Event, ValidationError};
+    ///
+    /// let mut ical = Calendar::create("-//Test//EN", "2.0", "GREGORIAN", "PUBLISH", "", "UTC");
+    /// let mut event = Event::empty();
+    /// event.uid = Some("1@example.com".to_string());
+    /// ical.add_event(event);
+    /// assert_eq!(
+    ///     ical.validate(),
+    ///     Err(vec![ValidationError::MissingProperty {
+    ///         event: 0,
+    ///         property: "DTSTAMP"
+    ///     }])
+    /// );
+    ///
+    /// ical.events[0].dtstamp = Some(Utc::now());
+    /// assert_eq!(ical.validate(), Ok(()));
+    ///
+    /// ical.method = None;
+    /// ical.prodid.clear();
+    /// assert_eq!(
+    ///     ical.validate(),
+    ///     Err(vec![
+    ///         ValidationError::MissingProdid,
+    ///         ValidationError::MissingProperty {
+    ///             event: 0,
+    ///             property: "DTSTART"
+    ///         },
+    ///     ])
+    /// );
+    ///
+    /// // the order of the properties doesn't matter, but an end needs a start
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      METHOD:PUBLISH\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      DTEND:20190522T100000Z\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTSTAMP:20190501T090000Z\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      DTEND:20190522T100000Z\r\n\
+    ///      DTSTAMP:20190501T090000Z\r\n\
+    ///      UID:2@example.com\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert!(ical.events[0].dtstart < ical.events[0].dtend);
+    /// assert_eq!(
+    ///     ical.validate(),
+    ///     Err(vec![ValidationError::MissingProperty {
+    ///         event: 1,
+    ///         property: "DTSTART"
+    ///     }])
+    /// );
+    /// ```
+    ///
+    /// # Inverted events
+    /// An event ending before it starts is kept as it is by default, but flagged here. With
+    /// [`ParseMode::Lenient`] its end is dropped while parsing.
+    /// ```
+    /// use web_ical::{Calendar, ParseMode, ParseOptions, ValidationError};
+    ///
+    /// let data = "BEGIN:VCALENDAR\r\n\
+    ///             PRODID:-//Test//EN\r\n\
+    ///             VERSION:2.0\r\n\
+    ///             BEGIN:VEVENT\r\n\
+    ///             UID:1@example.com\r\n\
+    ///             DTSTAMP:20190501T090000Z\r\n\
+    ///             DTSTART:20190522T100000Z\r\n\
+    ///             DTEND:20190522T090000Z\r\n\
+    ///             END:VEVENT\r\n\
+    ///             END:VCALENDAR\r\n";
+    /// let ical = Calendar::new_from_data(data).unwrap();
+    /// assert!(ical.events[0].dtend.is_some());
+    /// assert_eq!(ical.validate(), Err(vec![ValidationError::EndBeforeStart { event: 0 }]));
+    ///
+    /// let options = ParseOptions {
+    ///     mode: ParseMode::Lenient,
+    ///     ..ParseOptions::default()
+    /// };
+    /// let ical = Calendar::new_from_data_with_options(data, &options).unwrap();
+    /// assert_eq!(ical.events[0].dtend, None);
+    /// assert_eq!(ical.validate(), Ok(()));
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        if self.prodid.is_empty() {
+            errors.push(ValidationError::MissingProdid);
+        }
+        if self.version.is_empty() {
+            errors.push(ValidationError::MissingVersion);
+        }
+        for (event, ev) in self.events.iter().enumerate() {
+            for property in ev.check_consistency(self.method.is_some()) {
+                errors.push(ValidationError::MissingProperty { event, property });
+            }
+            if ev.ends_before_start() {
+                errors.push(ValidationError::EndBeforeStart { event });
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Whether the calendar is an iTIP invitation, i.e. its `METHOD` is `REQUEST`.
+    pub fn is_invitation(&self) -> bool {
+        self.method == Some(Method::Request)
+    }
+
+    /// Whether the calendar is an iTIP reply of an attendee, i.e. its `METHOD` is `REPLY`.
+    pub fn is_reply(&self) -> bool {
+        self.method == Some(Method::Reply)
+    }
+
+    /// Whether the calendar is an iTIP cancellation, i.e. its `METHOD` is `CANCEL`.
+    pub fn is_cancellation(&self) -> bool {
+        self.method == Some(Method::Cancel)
+    }
+
+    /// Apply a received iTIP cancellation to the events of this calendar, returning how many
+    /// were affected. Nothing happens if `cancel` is not a cancellation.
+    ///
+    /// Events with the `UID` of a cancelled event are marked `CANCELLED`, only the one replacing
+    /// the given occurrence if the cancelled event has a `RECURRENCE-ID`. Without such a
+    /// replacement the occurrence is excluded from its series instead.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use web_ical::{Calendar, EventStatus};
+    ///
+    /// let mut ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:sync@example.com\r\n\
+    ///      DTSTART:20190506T090000Z\r\n\
+    ///      RRULE:FREQ=WEEKLY\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:lunch@example.com\r\n\
+    ///      DTSTART:20190506T120000Z\r\n\
+    ///      SEQUENCE:0\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let cancel = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      METHOD:CANCEL\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:lunch@example.com\r\n\
+    ///      SEQUENCE:1\r\n\
+    ///      END:VEVENT\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:sync@example.com\r\n\
+    ///      RECURRENCE-ID:20190513T090000Z\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert!(cancel.is_cancellation());
+    /// assert_eq!(ical.apply_cancellation(&cancel), 2);
+    ///
+    /// assert_eq!(ical.events[0].status, None);
+    /// assert_eq!(
+    ///     ical.events[0].exdates,
+    ///     [Utc.with_ymd_and_hms(2019, 5, 13, 9, 0, 0).unwrap()]
+    /// );
+    /// assert_eq!(ical.events[1].status, Some(EventStatus::Cancelled));
+    /// assert_eq!(ical.events[1].sequence, Some(1));
+    ///
+    /// // only cancellations are applied
+    /// let mut request = cancel.clone();
+    /// request.method = Some("REQUEST".into());
+    /// assert_eq!(ical.apply_cancellation(&request), 0);
+    /// ```
+    pub fn apply_cancellation(&mut self, cancel: &Calendar) -> usize {
+        if !cancel.is_cancellation() {
+            return 0;
+        }
+        let mut affected = 0;
+        for cancelled in cancel.events.iter().filter(|ev| ev.uid.is_some()) {
+            let same_uid = |ev: &&mut Event| ev.uid == cancelled.uid;
+            let mut matched = 0;
+            for ev in self.events.iter_mut().filter(same_uid) {
+                if cancelled.recur_id.is_some() && ev.recur_id != cancelled.recur_id {
+                    continue;
+                }
+                ev.status = Some(EventStatus::Cancelled);
+                if cancelled.sequence > ev.sequence {
+                    ev.sequence = cancelled.sequence;
+                }
+                matched += 1;
+            }
+            if let (0, Some(recur_id)) = (matched, cancelled.recur_id) {
+                let series = self
+                    .events
+                    .iter_mut()
+                    .find(|ev| ev.uid == cancelled.uid && ev.recur_id.is_none());
+                if let Some(series) = series {
+                    if !series.exdates.contains(&recur_id) {
+                        series.exdates.push(recur_id);
+                    }
+                    matched += 1;
+                }
+            }
+            affected += matched;
+        }
+        affected
+    }
+
+    /// Export iCalendar to any `Write` implementer.
+    ///
+    /// `CREATED` and `LAST-MODIFIED` are optional and only written when set. Every content line,
+    /// including the final `END:VCALENDAR`, is terminated by a CRLF.
+    ///
+    /// # iCalendar to stdout
     /// ```
+    /// # let ical = web_ical::Calendar::new_from_data(
+    /// #     "BEGIN:VCALENDAR\r\nPRODID:-//My Business Inc//My Calendar 70.9054//EN\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n",
+    /// # ).unwrap();
     /// ical.export_to(&mut std::io::stdout()).expect("Could not export to stdout");
     /// ```
     ///
+    /// # Optional properties
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTEND:20190522T100000Z\r\n\
+    ///      DTSTAMP:20190501T090000Z\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DESCRIPTION:Sync\r\n\
+    ///      LOCATION:Office\r\n\
+    ///      SEQUENCE:0\r\n\
+    ///      STATUS:CONFIRMED\r\n\
+    ///      SUMMARY:Sync\r\n\
+    ///      TRANSP:OPAQUE\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let mut out = Vec::new();
+    /// ical.export_to(&mut out).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(!out.contains("CREATED"));
+    /// assert!(!out.contains("LAST-MODIFIED"));
+    /// assert!(out.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    /// ```
+    ///
+    /// # Descriptive properties
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      DTSTART:20190522T090000Z\r\n\
+    ///      DTEND:20190522T100000Z\r\n\
+    ///      DTSTAMP:20190501T090000Z\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DESCRIPTION:Sync\r\n\
+    ///      LOCATION:Office\r\n\
+    ///      SEQUENCE:0\r\n\
+    ///      STATUS:CONFIRMED\r\n\
+    ///      SUMMARY:Sync\r\n\
+    ///      TRANSP:OPAQUE\r\n\
+    ///      CLASS:PRIVATE\r\n\
+    ///      GEO:37.386013;-122.082932\r\n\
+    ///      PRIORITY:1\r\n\
+    ///      RECUR-ID:20190522T090000Z\r\n\
+    ///      URL:https://example.com/sync\r\n\
+    ///      ORGANIZER:mailto:boss@x.com\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// let mut out = Vec::new();
+    /// ical.export_to(&mut out).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(out.contains("CLASS:PRIVATE\r\n"));
+    /// assert!(out.contains("GEO:37.386013;-122.082932\r\n"));
+    /// assert!(out.contains("PRIORITY:1\r\n"));
+    /// assert!(out.contains("RECURRENCE-ID:20190522T090000Z\r\n"));
+    /// assert!(out.contains("URL:https://example.com/sync\r\n"));
+    /// assert!(out.contains("ORGANIZER:mailto:boss@x.com\r\n"));
+    /// ```
+    ///
+    /// # Events without a start
+    /// iTIP messages, i.e. calendars with a `METHOD`, may reference an event by its UID only.
+    /// Properties an event lacks are left out.
+    /// ```
+    /// use web_ical::{Calendar, Method};
+    ///
+    /// let ical = Calendar::new_from_data(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      PRODID:-//Test//EN\r\n\
+    ///      VERSION:2.0\r\n\
+    ///      METHOD:CANCEL\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      UID:1@example.com\r\n\
+    ///      DTSTAMP:20190501T090000Z\r\n\
+    ///      SEQUENCE:1\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(ical.method, Some(Method::Cancel));
+    /// assert!(ical.validate().is_ok());
+    /// let mut out = Vec::new();
+    /// ical.export_to(&mut out).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(out.contains("METHOD:CANCEL\r\n"));
+    /// assert!(out.contains("BEGIN:VEVENT\r\nDTSTAMP:20190501T090000Z\r\nUID:1@example.com\r\n"));
+    /// assert!(!out.contains("DTSTART"));
+    /// assert!(!out.contains("SUMMARY"));
+    /// assert!(out.contains("SEQUENCE:1\r\n"));
+    /// ```
     pub fn export_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
-        write!(writer, "BEGIN:VCALENDAR\r\n")?;
-        write!(writer, "PRODID:{}\r\n", &self.prodid)?;
+        self.write_events(writer, None, &ExportOptions::default())
+    }
+
+    /// Export iCalendar to any `Write` implementer, as configured by `options`.
+    ///
+    /// ```
+    /// use web_ical::{Calendar, ExportOptions};
+    ///
+    /// let ical = Calendar::create("-//Test//EN", "2.0", "GREGORIAN", "PUBLISH", "", "UTC");
+    ///
+    /// let mut out = Vec::new();
+    /// ical.export_to_with_options(&mut out, &ExportOptions::default())
+    ///     .unwrap();
+    /// assert!(out.ends_with(b"END:VCALENDAR\r\n"));
+    ///
+    /// let options = ExportOptions {
+    ///     trailing_crlf: false,
+    ///     ..ExportOptions::default()
+    /// };
+    /// let mut out = Vec::new();
+    /// ical.export_to_with_options(&mut out, &options).unwrap();
+    /// assert!(out.ends_with(b"END:VCALENDAR"));
+    /// ```
+    pub fn export_to_with_options<W: Write>(
+        &self,
+        writer: &mut W,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        self.write_events(writer, None, options)
+    }
+
+    /// Export only the events which may be shown to anyone, as configured by `options`.
+    ///
+    /// Events classified as `PRIVATE`, `CONFIDENTIAL` or with an unknown `CLASS` are left out,
+    /// events without a `CLASS` count as public. Like with [`Calendar::export_changed_since_to`],
+    /// journals and free/busy components are left out too.
+    ///
+    /// ```
+    /// use web_ical::{Calendar, ExportOptions};
+    ///
+    /// let event = |uid: &str, class: &str| {
+    ///     format!(
+    ///         "BEGIN:VEVENT\r\n\
+    ///          DTSTART:20190522T090000Z\r\n\
+    ///          DTEND:20190522T100000Z\r\n\
+    ///          DTSTAMP:20190501T090000Z\r\n\
+    ///          UID:{}\r\n\
+    ///          DESCRIPTION:Sync\r\n\
+    ///          LOCATION:Office\r\n\
+    ///          SEQUENCE:0\r\n\
+    ///          STATUS:CONFIRMED\r\n\
+    ///          SUMMARY:Sync\r\n\
+    ///          TRANSP:OPAQUE\r\n\
+    ///          {}\
+    ///          ORGANIZER:mailto:boss@example.com\r\n\
+    ///          ATTENDEE:mailto:jane@example.com\r\n\
+    ///          END:VEVENT\r\n",
+    ///         uid, class
+    ///     )
+    /// };
+    /// let data = format!(
+    ///     "BEGIN:VCALENDAR\r\nPRODID:-//Test//EN\r\nVERSION:2.0\r\n{}{}{}END:VCALENDAR\r\n",
+    ///     event("public@example.com", "CLASS:PUBLIC\r\n"),
+    ///     event("private@example.com", "CLASS:PRIVATE\r\n"),
+    ///     event("unclassified@example.com", ""),
+    /// );
+    /// let ical = Calendar::new_from_data(&data).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// ical.export_public_to(&mut out, &ExportOptions::default()).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(out.contains("UID:public@example.com\r\n"));
+    /// assert!(out.contains("UID:unclassified@example.com\r\n"));
+    /// assert!(!out.contains("UID:private@example.com\r\n"));
+    /// assert!(out.contains("ATTENDEE:mailto:jane@example.com\r\n"));
+    ///
+    /// let options = ExportOptions {
+    ///     redact_participants: true,
+    ///     ..ExportOptions::default()
+    /// };
+    /// let mut out = Vec::new();
+    /// ical.export_public_to(&mut out, &options).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert_eq!(out.matches("BEGIN:VEVENT").count(), 2);
+    /// assert!(!out.contains("ORGANIZER"));
+    /// assert!(!out.contains("ATTENDEE"));
+    /// ```
+    pub fn export_public_to<W: Write>(
+        &self,
+        writer: &mut W,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        let public = |ev: &Event| matches!(ev.class, None | Some(Classification::Public));
+        self.write_events(writer, Some(&public), options)
+    }
+
+    /// Export only the events changed after `since` for incremental updates, e.g. a CalDAV push.
+    ///
+    /// An event counts as changed if its `LAST-MODIFIED`, or its `DTSTAMP` if it has none, is
+    /// after `since`. The calendar properties and timezones are written as usual, journals and
+    /// free/busy components are left out.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use web_ical::Calendar;
+    ///
+    /// let event = |uid: &str, modified: &str| {
+    ///     format!(
+    ///         "BEGIN:VEVENT\r\n\
+    ///          DTSTART:20190522T090000Z\r\n\
+    ///          DTEND:20190522T100000Z\r\n\
+    ///          DTSTAMP:20190501T090000Z\r\n\
+    ///          UID:{}\r\n\
+    ///          DESCRIPTION:Sync\r\n\
+    ///          {}\
+    ///          LOCATION:Office\r\n\
+    ///          SEQUENCE:0\r\n\
+    ///          STATUS:CONFIRMED\r\n\
+    ///          SUMMARY:Sync\r\n\
+    ///          TRANSP:OPAQUE\r\n\
+    ///          END:VEVENT\r\n",
+    ///         uid, modified
+    ///     )
+    /// };
+    /// let data = format!(
+    ///     "BEGIN:VCALENDAR\r\nPRODID:-//Test//EN\r\nVERSION:2.0\r\n{}{}{}END:VCALENDAR\r\n",
+    ///     event("old@example.com", "LAST-MODIFIED:20190502T090000Z\r\n"),
+    ///     event("new@example.com", "LAST-MODIFIED:20190510T090000Z\r\n"),
+    ///     event("stamped@example.com", ""),
+    /// );
+    /// let ical = Calendar::new_from_data(&data).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// let since = Utc.with_ymd_and_hms(2019, 5, 5, 0, 0, 0).unwrap();
+    /// ical.export_changed_since_to(since, &mut out).unwrap();
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert!(out.contains("UID:new@example.com\r\n"));
+    /// assert!(!out.contains("UID:old@example.com\r\n"));
+    /// assert!(!out.contains("UID:stamped@example.com\r\n"));
+    ///
+    /// let mut out = Vec::new();
+    /// let since = Utc.with_ymd_and_hms(2019, 4, 1, 0, 0, 0).unwrap();
+    /// ical.export_changed_since_to(since, &mut out).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap().matches("BEGIN:VEVENT").count(), 3);
+    /// ```
+    pub fn export_changed_since_to<W: Write>(
+        &self,
+        since: DateTime<Utc>,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        let changed = |ev: &Event| ev.last_modified.or(ev.dtstamp).is_some_and(|at| at > since);
+        self.write_events(writer, Some(&changed), &ExportOptions::default())
+    }
+
+    /// Write the properties of the calendar, in their default order.
+    fn write_properties<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_property(writer, &format!("PRODID:{}", self.prodid))?;
         if let Some(scale) = self.calscale.as_ref() {
-            write!(writer, "CALSCALE:{}\r\n", scale)?;
+            write_property(writer, &format!("CALSCALE:{}", scale))?;
         }
-        write!(writer, "VERSION:{}\r\n", &self.version)?;
+        write_property(writer, &format!("VERSION:{}", &self.version))?;
         if let Some(method) = self.method.as_ref() {
-            write!(writer, "METHOD:{}\r\n", method)?;
+            write_property(writer, &format!("METHOD:{}", method))?;
+        }
+        if let Some(name) = self.name.as_ref() {
+            write_property(writer, &format!("NAME:{}", name))?;
         }
         if let Some(val) = self.x_wr_calname.as_ref() {
-            write!(writer, "X-WR-CALNAME:{}\r\n", val)?;
+            write_property(writer, &format!("X-WR-CALNAME:{}", val))?;
         }
         if let Some(tz) = self.x_wr_timezone.as_ref() {
-            write!(writer, "X-WR-TIMEZONE:{}\r\n", tz)?;
+            write_property(writer, &format!("X-WR-TIMEZONE:{}", tz))?;
         }
-        for i in &self.events {
-            write!(writer, "BEGIN:VEVENT\r\n")?;
-            write!(
-                writer,
-                "DTSTART:{}\r\n",
-                &i.dtstart.as_ref().unwrap().format("%Y%m%dT%H%M%SZ")
-            )?;
-            write!(
-                writer,
-                "DTEND:{}\r\n",
-                &i.dtend.as_ref().unwrap().format("%Y%m%dT%H%M%SZ")
-            )?;
-            write!(
-                writer,
-                "DTSTAMP:{}\r\n",
-                &i.dtstamp.as_ref().unwrap().format("%Y%m%dT%H%M%SZ")
-            )?;
-            write!(writer, "UID:{}\r\n", &i.uid.as_ref().unwrap())?;
-            write!(
-                writer,
-                "CREATED:{}\r\n",
-                &i.created.as_ref().unwrap().format("%Y%m%dT%H%M%SZ")
-            )?;
-            write!(
-                writer,
-                "DESCRIPTION:{}\r\n",
-                &i.description.as_ref().unwrap()
-            )?;
-            write!(
+        if let Some(color) = self.color.as_ref() {
+            write_property(writer, &format!("COLOR:{}", color))?;
+        }
+        if let Some(interval) = self.refresh_interval {
+            write_property(
                 writer,
-                "LAST-MODIFIED:{}\r\n",
-                &i.last_modified.as_ref().unwrap().format("%Y%m%dT%H%M%SZ")
+                &format!(
+                    "REFRESH-INTERVAL;VALUE=DURATION:{}",
+                    format_duration(interval)
+                ),
             )?;
-            write!(writer, "LOCATION:{}\r\n", &i.location.as_ref().unwrap())?;
-            write!(writer, "SEQUENCE:{}\r\n", &i.sequence.as_ref().unwrap())?;
-            write!(writer, "STATUS:{}\r\n", &i.status.as_ref().unwrap())?;
-            write!(writer, "SUMMARY:{}\r\n", &i.summary.as_ref().unwrap())?;
-            write!(writer, "TRANSP:{}\r\n", &i.transp.as_ref().unwrap())?;
+        }
+        if !self.categories.is_empty() {
+            let categories: Vec<String> =
+                self.categories.iter().map(|cat| escape_text(cat)).collect();
+            write_property(writer, &format!("CATEGORIES:{}", categories.join(",")))?;
+        }
+        for (key, value) in &self.extra {
+            write_property(writer, &format!("{}:{}", key, value))?;
+        }
+        Ok(())
+    }
+
+    /// Write the calendar, only with the events accepted by `filter` if it is given.
+    fn write_events<W: Write>(
+        &self,
+        writer: &mut W,
+        filter: Option<&dyn Fn(&Event) -> bool>,
+        options: &ExportOptions,
+    ) -> io::Result<()> {
+        write!(writer, "BEGIN:VCALENDAR\r\n")?;
+        if options.preserve_order && !self.property_order.is_empty() {
+            let mut properties = vec![];
+            self.write_properties(&mut properties)?;
+            write_in_order(writer, &properties, &self.property_order)?;
+        } else {
+            self.write_properties(writer)?;
+        }
+        for tz in &self.timezones {
+            write!(writer, "BEGIN:VTIMEZONE\r\n")?;
+            write_property(writer, &format!("TZID:{}", tz.tzid))?;
+            for line in &tz.lines {
+                write_property(writer, line)?;
+            }
+            write!(writer, "END:VTIMEZONE\r\n")?;
+        }
+        let included = |ev: &&Event| filter.map_or(true, |filter| filter(ev));
+        for event in self.events.iter().filter(included) {
+            write!(writer, "BEGIN:VEVENT\r\n")?;
+            if options.preserve_order && !event.property_order.is_empty() {
+                let mut properties = vec![];
+                write_event_properties(&mut properties, event, options)?;
+                write_in_order(writer, &properties, &event.property_order)?;
+            } else {
+                write_event_properties(writer, event, options)?;
+            }
             write!(writer, "END:VEVENT\r\n")?;
         }
+        if filter.is_none() {
+            for journal in &self.journals {
+                journal.write_component(writer)?;
+            }
+            for freebusy in &self.freebusy {
+                freebusy.write_component(writer)?;
+            }
+        }
         write!(writer, "END:VCALENDAR")?;
+        if options.trailing_crlf {
+            write!(writer, "\r\n")?;
+        }
         Ok(())
     }
 
+    /// Export iCalendar to any `AsyncWrite` implementer, e.g. to stream an HTTP response.
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::create(
+    ///     "-//My Business Inc//My Calendar 70.9054//EN",
+    ///     "2.0",
+    ///     "GREGORIAN",
+    ///     "PUBLISH",
+    ///     "example@gmail.com",
+    ///     "America/New_York",
+    /// );
+    /// let mut out: Vec<u8> = Vec::new();
+    /// ical.export_to_async(&mut out).await.unwrap();
+    /// assert!(String::from_utf8(out).unwrap().starts_with("BEGIN:VCALENDAR\r\n"));
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn export_to_async<W: tokio::io::AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut data = Vec::new();
+        self.export_to(&mut data)?;
+        writer.write_all(&data).await?;
+        writer.flush().await
+    }
+
     ///Export iCalendar to a file.
     ///
+    /// This writes the same output as [`Calendar::export_to`], the returned `bool` is always
+    /// `true` and only kept for backward compatibility.
+    ///
     /// # iCalendar to a file
-    /// ```
+    /// ```no_run
+    /// # let ical = web_ical::Calendar::new_from_data(
+    /// #     "BEGIN:VCALENDAR\r\nPRODID:-//My Business Inc//My Calendar 70.9054//EN\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n",
+    /// # ).unwrap();
     ///  match ical.export_ics("ical.ics"){
     ///        Ok(_) => println!("OK"),
     ///        Err(_) => panic!("Err")
     ///    };
     /// ```
     pub fn export_ics(&self, path: &str) -> io::Result<bool> {
-        let mut data = "BEGIN:VCALENDAR\r\n".to_string();
-        let path = Path::new(path);
-        let mut f = File::create(&path)?;
-        data.push_str("PRODID:");
-        data.push_str(&self.prodid);
-        if let Some(scale) = self.calscale.as_ref() {
-            data.push_str("\r\n");
-            data.push_str("CALSCALE:");
-            data.push_str(scale);
+        let mut writer = BufWriter::new(File::create(Path::new(path))?);
+        self.export_to(&mut writer)?;
+        writer.flush()?;
+        Ok(true)
+    }
+
+    /// Serialize the iCalendar into a `String`, e.g. for an HTTP response body.
+    ///
+    /// The output is the same as [`Calendar::export_to`].
+    ///
+    /// ```
+    /// use web_ical::Calendar;
+    ///
+    /// let ical = Calendar::create(
+    ///     "-//My Business Inc//My Calendar 70.9054//EN",
+    ///     "2.0",
+    ///     "GREGORIAN",
+    ///     "PUBLISH",
+    ///     "example@gmail.com",
+    ///     "America/New_York",
+    /// );
+    /// let out = ical.to_ics_string();
+    /// assert!(out.starts_with("BEGIN:VCALENDAR\r\n"));
+    /// assert!(out.ends_with("END:VCALENDAR\r\n"));
+    /// assert_eq!(ical.to_string(), out);
+    /// ```
+    pub fn to_ics_string(&self) -> String {
+        let mut data = Vec::new();
+        self.export_to(&mut data)
+            .expect("writing to a Vec can not fail");
+        String::from_utf8(data).expect("export_to only writes UTF-8")
+    }
+}
+
+impl fmt::Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_ics_string())
+    }
+}
+
+/// Parse a calendar like [`Calendar::new_from_data`] does.
+///
+/// ```
+/// use web_ical::Calendar;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let text = "BEGIN:VCALENDAR\r\n\
+///             PRODID:-//Test//EN\r\n\
+///             VERSION:2.0\r\n\
+///             BEGIN:VEVENT\r\n\
+///             UID:1@example.com\r\n\
+///             SUMMARY:Sync\r\n\
+///             END:VEVENT\r\n\
+///             END:VCALENDAR\r\n";
+/// let cal: Calendar = text.parse()?;
+/// assert_eq!(cal.events[0].summary.as_deref(), Some("Sync"));
+/// assert_eq!(cal.to_string().parse::<Calendar>()?, cal);
+/// # Ok(())
+/// # }
+/// ```
+impl std::str::FromStr for Calendar {
+    type Err = anyhow::Error;
+
+    fn from_str(data: &str) -> anyhow::Result<Calendar> {
+        Calendar::new_from_data(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// The text of a calendar with the given properties, holding one event with the given
+    /// properties after its `UID`.
+    fn ics(calendar: &[&str], event: &[&str]) -> String {
+        let mut lines = vec!["BEGIN:VCALENDAR", "PRODID:-//Test//EN", "VERSION:2.0"];
+        lines.extend_from_slice(calendar);
+        lines.extend_from_slice(&["BEGIN:VEVENT", "UID:1@example.com"]);
+        lines.extend_from_slice(event);
+        lines.extend_from_slice(&["END:VEVENT", "END:VCALENDAR", ""]);
+        lines.join("\r\n")
+    }
+
+    fn parse(calendar: &[&str], event: &[&str]) -> Calendar {
+        Calendar::new_from_data(&ics(calendar, event)).unwrap()
+    }
+
+    fn event(properties: &[&str]) -> Event {
+        parse(&[], properties).events.remove(0)
+    }
+
+    fn strict() -> ParseOptions {
+        ParseOptions {
+            mode: ParseMode::Strict,
+            ..ParseOptions::default()
         }
-        data.push_str("\r\n");
-        data.push_str("VERSION:");
-        data.push_str(&self.version);
-        if let Some(method) = self.method.as_ref() {
-            data.push_str("\r\n");
-            data.push_str("METHOD:");
-            data.push_str(method);
+    }
+
+    fn reparse(ical: &Calendar) -> Calendar {
+        Calendar::new_from_data(&ical.to_ics_string()).unwrap()
+    }
+
+    fn pairs(params: &[(&str, &str)]) -> Vec<(String, String)> {
+        params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn params() {
+        assert_eq!(parse_params("SUMMARY"), ("SUMMARY", vec![]));
+        assert_eq!(
+            parse_params("DTSTART;tzid=Europe/Berlin"),
+            ("DTSTART", pairs(&[("TZID", "Europe/Berlin")]))
+        );
+        assert_eq!(
+            parse_params("ATTENDEE;CN=\"Doe; John: Jr.\";ROLE=CHAIR"),
+            (
+                "ATTENDEE",
+                pairs(&[("CN", "Doe; John: Jr."), ("ROLE", "CHAIR")])
+            )
+        );
+        assert_eq!(
+            parse_params("DESCRIPTION;ALTREP=\"cid:part1.0001@example.org\""),
+            (
+                "DESCRIPTION",
+                pairs(&[("ALTREP", "cid:part1.0001@example.org")])
+            )
+        );
+    }
+
+    #[test]
+    fn byte_order_marks() {
+        let data = ics(&[], &["SUMMARY:R\u{e9}union"]);
+        let utf16 = |le: bool| -> Vec<u8> {
+            std::iter::once('\u{feff}')
+                .chain(data.chars())
+                .collect::<String>()
+                .encode_utf16()
+                .flat_map(|unit| {
+                    if le {
+                        unit.to_le_bytes()
+                    } else {
+                        unit.to_be_bytes()
+                    }
+                })
+                .collect()
+        };
+        for bytes in [utf16(true), utf16(false)].iter() {
+            let ical = Calendar::new_from_bytes(bytes).unwrap();
+            assert_eq!(ical.events[0].summary.as_deref(), Some("R\u{e9}union"));
+        }
+        let utf8 = format!("\u{feff}{}", data);
+        let ical = Calendar::new_from_bytes(utf8.as_bytes()).unwrap();
+        assert_eq!(ical.events[0].summary.as_deref(), Some("R\u{e9}union"));
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn mmap_encodings() {
+        let data = ics(&[], &["SUMMARY:R\u{e9}union"]);
+        let path = std::env::temp_dir().join("web_ical_mmap_encodings.ics");
+        let mut utf16 = vec![0xff, 0xfe];
+        utf16.extend(data.encode_utf16().flat_map(u16::to_le_bytes));
+        let latin1: Vec<u8> = data.chars().map(|c| c as u8).collect();
+        for bytes in [utf16, latin1, data.clone().into_bytes()].iter() {
+            std::fs::write(&path, bytes).unwrap();
+            let ical = Calendar::from_mmap(&path).unwrap();
+            assert_eq!(ical.events[0].summary.as_deref(), Some("R\u{e9}union"));
         }
-        if let Some(calname) = self.x_wr_calname.as_ref() {
-            data.push_str("\r\n");
-            data.push_str("X-WR-CALNAME:");
-            data.push_str(calname);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn duration_overflow() {
+        assert_eq!(parse_duration("-PT15M").unwrap(), Duration::minutes(-15));
+        assert_eq!(parse_duration("P1DT2H").unwrap(), Duration::hours(26));
+        for value in [
+            "P99999999999W",
+            "-P99999999999W",
+            "P9223372036854775807D",
+            "PT9223372036854775807S",
+            "P99999999999999999999D",
+            "P106751991167DT24H",
+        ]
+        .iter()
+        {
+            assert!(parse_duration(value).is_err(), "{}", value);
         }
-        if let Some(tz) = self.x_wr_timezone.as_ref() {
-            data.push_str("\r\n");
-            data.push_str("X-WR-TIMEZONE:");
-            data.push_str(tz);
-        }
-        data.push_str("\r\n");
-        for i in &self.events {
-            data.push_str("BEGIN:VEVENT\r\n");
-            data.push_str("DTSTART:");
-            data.push_str(
-                &i.dtstart
-                    .as_ref()
-                    .unwrap()
-                    .format("%Y%m%dT%H%M%SZ")
-                    .to_string(),
-            );
-            data.push_str("\r\n");
-            data.push_str("DTEND:");
-            data.push_str(
-                &i.dtend
-                    .as_ref()
-                    .unwrap()
-                    .format("%Y%m%dT%H%M%SZ")
-                    .to_string(),
-            );
-            data.push_str("\r\n");
-            data.push_str("DTSTAMP:");
-            data.push_str(
-                &i.dtstamp
-                    .as_ref()
-                    .unwrap()
-                    .format("%Y%m%dT%H%M%SZ")
-                    .to_string(),
-            );
-            data.push_str("\r\n");
-            data.push_str("UID:");
-            data.push_str(&i.uid.as_ref().unwrap());
-            data.push_str("\r\n");
-            data.push_str("CREATED:");
-            data.push_str(
-                &i.created
-                    .as_ref()
-                    .unwrap()
-                    .format("%Y%m%dT%H%M%SZ")
-                    .to_string(),
-            );
-            data.push_str("\r\n");
-            data.push_str("DESCRIPTION:");
-            data.push_str(&i.description.as_ref().unwrap());
-            data.push_str("\r\n");
-            data.push_str("LAST-MODIFIED:");
-            data.push_str(
-                &i.last_modified
-                    .as_ref()
-                    .unwrap()
-                    .format("%Y%m%dT%H%M%SZ")
-                    .to_string(),
+
+        let event = event(&[
+            "DTSTART:20190522T090000Z",
+            "DURATION:P99999999999W",
+            "BEGIN:VALARM",
+            "ACTION:DISPLAY",
+            "TRIGGER:-P99999999999W",
+            "END:VALARM",
+        ]);
+        assert_eq!(event.duration, None);
+        assert_eq!(event.alarms[0].trigger, None);
+
+        // valid durations reaching past the latest representable time
+        let ical = parse(&[], &["DTSTART:20190522T090000Z", "DURATION:P100000000D"]);
+        let start = Utc.with_ymd_and_hms(2019, 5, 22, 9, 0, 0).unwrap();
+        let event = &ical.events[0];
+        assert_eq!(event.duration, Some(Duration::days(100_000_000)));
+        assert!(event.overlaps(event));
+        assert_eq!(
+            event.time_until(start + Duration::days(1)),
+            Some(Duration::days(-1))
+        );
+        assert_eq!(
+            ical.events_between(start, start + Duration::days(1)).len(),
+            1
+        );
+        assert!(parse_period("20190522T090000Z/P100000000D").is_err());
+    }
+
+    fn unfold(data: &[u8]) -> Vec<String> {
+        let raw = &mut LineReader::new(Cursor::new(data));
+        let mut lines = vec![];
+        let mut buf = String::new();
+        while next_line(raw, &mut buf).is_ok() {
+            lines.push(buf.clone());
+        }
+        lines
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gunzip_limit() {
+        use flate2::{write::GzEncoder, Compression};
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(&[b'x'; 1000]).unwrap();
+        let gzip = gzip.finish().unwrap();
+        assert_eq!(gunzip_unlabeled(&gzip, 1000).unwrap().len(), 1000);
+        assert!(gunzip_unlabeled(&gzip, 999).is_err());
+        assert_eq!(gunzip_unlabeled(b"BEGIN", 1).unwrap(), &b"BEGIN"[..]);
+    }
+
+    #[test]
+    fn webcal_urls() {
+        assert_eq!(
+            resolve_webcal("webcal://p01-calendars.icloud.com/holidays/us_en.ics"),
+            "https://p01-calendars.icloud.com/holidays/us_en.ics"
+        );
+        assert_eq!(
+            resolve_webcal("WEBCALS://example.com/feed.ics"),
+            "https://example.com/feed.ics"
+        );
+        assert_eq!(
+            resolve_webcal("http://example.com/feed.ics"),
+            "http://example.com/feed.ics"
+        );
+        assert_eq!(resolve_webcal("webcalendar.ics"), "webcalendar.ics");
+    }
+
+    #[test]
+    fn unfolding() {
+        assert_eq!(
+            unfold(b"SUMMARY:Long\r\n  summary\r\n\tcontinued\r\nUID:1\r\n"),
+            ["SUMMARY:Long summarycontinued", "UID:1"]
+        );
+        // bare LF line endings and a last line without one
+        assert_eq!(unfold(b"SUMMARY:Lo\n ng\nUID:1"), ["SUMMARY:Long", "UID:1"]);
+        // a fold in the middle of the two octets of an é
+        assert_eq!(
+            unfold(b"SUMMARY:R\xc3\r\n \xa9union\r\n"),
+            ["SUMMARY:R\u{e9}union"]
+        );
+        // blank lines are lines of their own, while a folded empty line adds nothing
+        assert_eq!(unfold(b"A:1\r\n\r\n \r\nB:2\r\n"), ["A:1", "", "B:2"]);
+        assert!(next_line(
+            &mut LineReader::new(Cursor::new(b"A:\xc3\r\n")),
+            &mut String::new()
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn stream_unfolding() {
+        use futures::StreamExt;
+
+        let mut bytes = ics(&[], &["SUMMARY:R\u{e9}union"]).into_bytes();
+        // fold in the middle of the two octets of the é
+        let second = bytes.windows(2).position(|w| w == b"\xc3\xa9").unwrap() + 1;
+        bytes.splice(second..second, b"\r\n ".iter().copied());
+        let events: Vec<_> = Calendar::events_stream(&bytes[..]).collect().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].as_ref().unwrap().summary.as_deref(),
+            Some("R\u{e9}union")
+        );
+    }
+
+    #[test]
+    fn folding_round_trip() {
+        let text = "Jahresrückblick und Planung für das nächste Quartal – alle Abteilungen";
+        let attendee = "ATTENDEE;CN=\"Marie-Luise Müller-Lüdenscheidt\";ROLE=REQ-PARTICIPANT;\
+                        PARTSTAT=ACCEPTED:mailto:marie-luise.mueller@example.com";
+        let ical = parse(
+            &[],
+            &[
+                &format!("DESCRIPTION:{}", text),
+                &format!("SUMMARY:{}", text),
+                &format!("LOCATION:{}", text),
+                attendee,
+            ],
+        );
+        let out = ical.to_ics_string();
+        assert!(out.lines().all(|line| line.len() <= 75));
+        assert!(!out.contains(text));
+        let event = &reparse(&ical).events[0];
+        assert_eq!(event.description.as_deref(), Some(text));
+        assert_eq!(event.summary.as_deref(), Some(text));
+        assert_eq!(event.location.as_deref(), Some(text));
+        assert_eq!(event.attendees, ical.events[0].attendees);
+        assert_eq!(event.attendees[0].email, "marie-luise.mueller@example.com");
+    }
+
+    #[test]
+    fn sloppy_dtstart() {
+        let start = |value: &str| event(&[&format!("DTSTART:{}", value)]).dtstart;
+        let full = Utc.with_ymd_and_hms(2019, 5, 22, 23, 27, 1).unwrap();
+        assert_eq!(start("20190522T232701Z"), Some(full));
+        assert_eq!(start("20190522T232701"), Some(full));
+        assert_eq!(start("20190522T232701.000Z"), Some(full));
+        assert_eq!(start("20190522T232701.000"), Some(full));
+        let minutes = Utc.with_ymd_and_hms(2019, 5, 22, 23, 27, 0).unwrap();
+        assert_eq!(start("20190522T2327"), Some(minutes));
+        assert_eq!(start("20190522T2327Z"), Some(minutes));
+        assert_eq!(start("2019-05-22"), None);
+    }
+
+    #[test]
+    fn description_altrep() {
+        let ical = parse(
+            &[],
+            &[
+                "DESCRIPTION;ALTREP=\"data:text/html,%3Cb%3EBring%3C%2Fb%3E%20the%20slides\":",
+                " Bring the slides",
+            ],
+        );
+        let event = &ical.events[0];
+        assert_eq!(event.description.as_deref(), Some("Bring the slides"));
+        assert_eq!(
+            event.description_html.as_deref(),
+            Some("<b>Bring</b> the slides")
+        );
+
+        let out = ical.to_ics_string();
+        assert!(
+            out.contains("DESCRIPTION;ALTREP=\"data:text/html,%3Cb%3EBring%3C%2Fb%3E%20the%20s")
+        );
+        assert_eq!(reparse(&ical).events[0], *event);
+
+        for uri in [
+            "cid:part1.0001@example.org",
+            "http://example.com/agenda.html",
+        ] {
+            let altrep = format!("DESCRIPTION;ALTREP=\"{}\":Agenda", uri);
+            let ical = parse(&[], &[&altrep]);
+            let event = &ical.events[0];
+            assert_eq!(event.description_html, None);
+            assert_eq!(event.description_altrep.as_deref(), Some(uri));
+            assert!(ical.to_ics_string().contains(&format!("{}\r\n", altrep)));
+            assert_eq!(reparse(&ical).events[0], *event);
+        }
+    }
+
+    #[test]
+    fn quoted_printable() {
+        let ical = parse(
+            &[],
+            &[
+                "DESCRIPTION;ENCODING=QUOTED-PRINTABLE:C:\\Temp=5Cnotes=3B =",
+                "ok=0D=0Anext line",
+                "LOCATION;CHARSET=ISO-8859-1;ENCODING=QUOTED-PRINTABLE;X-ROOM=4:Caf=E9",
+                "SUMMARY:about QUOTED-PRINTABLE=",
+            ],
+        );
+        let event = &ical.events[0];
+        assert_eq!(
+            event.description.as_deref(),
+            Some("C:\\Temp\\notes; ok\nnext line")
+        );
+        assert_eq!(event.location.as_deref(), Some("Caf\u{e9}"));
+        assert_eq!(event.location_params, pairs(&[("X-ROOM", "4")]));
+        assert_eq!(event.summary.as_deref(), Some("about QUOTED-PRINTABLE="));
+
+        let out = ical.to_ics_string();
+        assert!(out.contains("DESCRIPTION:C:\\\\Temp\\\\notes\\; ok\\nnext line\r\n"));
+        assert!(out.contains("LOCATION;X-ROOM=4:Caf\u{e9}\r\n"));
+        assert_eq!(reparse(&ical).events[0], *event);
+    }
+
+    #[test]
+    fn text_language() {
+        let ical = parse(
+            &[],
+            &[
+                "SUMMARY;LANGUAGE=de:Besprechung",
+                "DESCRIPTION;LANGUAGE=de-CH:Wöchentliche Besprechung",
+            ],
+        );
+        let event = &ical.events[0];
+        assert_eq!(event.summary.as_deref(), Some("Besprechung"));
+        assert_eq!(event.summary_language.as_deref(), Some("de"));
+        assert_eq!(event.description_language.as_deref(), Some("de-CH"));
+
+        let out = ical.to_ics_string();
+        assert!(out.contains("SUMMARY;LANGUAGE=de:Besprechung\r\n"));
+        assert!(out.contains("DESCRIPTION;LANGUAGE=de-CH:Wöchentliche Besprechung\r\n"));
+        assert_eq!(reparse(&ical).events[0], *event);
+    }
+
+    #[test]
+    fn rrule_round_trip() {
+        let ical = parse(
+            &[],
+            &[
+                "DTSTART:20190522T090000Z",
+                "RRULE:FREQ=WEEKLY;UNTIL=20191231T235959Z;INTERVAL=2",
+            ],
+        );
+        let out = ical.to_ics_string();
+        assert!(out.contains("RRULE:FREQ=WEEKLY;UNTIL=20191231T235959Z;INTERVAL=2\r\n"));
+
+        let ical = Calendar::new_from_data(&out).unwrap();
+        let repeat = ical.events[0].repeat.as_ref().unwrap();
+        assert_eq!(repeat.freq, Frequency::Weekly);
+        assert_eq!(repeat.interval, Some(2));
+        assert!(repeat.until.is_some());
+    }
+
+    #[test]
+    fn rrule_errors() {
+        let repeat = event(&["DTSTART:20190513T090000Z", "RRULE:FREQ=DAILY;COUNT=3;"]).repeat;
+        assert_eq!(repeat.unwrap().count, Some(3));
+        let repeat = event(&["RRULE:FREQ=DAILY;BYDAY;COUNT=3"]).repeat;
+        assert_eq!(repeat.unwrap().count, Some(3));
+
+        for part in ["UNTIL=20190532", "COUNT=-3", "INTERVAL=2.5"] {
+            let rrule = format!("RRULE:FREQ=WEEKLY;{}", part);
+            let data = ics(&[], &[&rrule]);
+            let (ical, warnings) = Calendar::parse_verbose(&data);
+            let ical = ical.unwrap();
+            assert_eq!(ical.events[0].repeat, Some(Repeat::new(Frequency::Weekly)));
+            assert!(warnings
+                .iter()
+                .any(|warning| warning.to_string().contains(part)));
+            assert!(Calendar::new_from_data_with_options(&data, &strict()).is_err());
+        }
+    }
+
+    #[test]
+    fn rrule_until_date() {
+        let event = event(&[
+            "DTSTART;VALUE=DATE:20250106",
+            "DTEND;VALUE=DATE:20250107",
+            "RRULE:FREQ=WEEKLY;UNTIL=20251231",
+        ]);
+        let repeat = event.repeat.as_ref().unwrap();
+        assert_eq!(repeat.freq, Frequency::Weekly);
+        assert_eq!(
+            repeat.until,
+            Some(Utc.with_ymd_and_hms(2025, 12, 31, 23, 59, 59).unwrap())
+        );
+
+        let mut ical = parse(&[], &[]);
+        ical.events[0] = event;
+        let out = ical.to_ics_string();
+        assert!(out.contains("RRULE:FREQ=WEEKLY;UNTIL=20251231\r\n"));
+        assert_eq!(reparse(&ical).events[0], ical.events[0]);
+    }
+
+    #[test]
+    fn date_lists() {
+        let event = event(&[
+            "DTSTART:20200101T090000Z",
+            "RRULE:FREQ=MONTHLY;COUNT=4",
+            "EXDATE:20200101T090000Z,20200201T090000Z,20200301T090000Z",
+            "RDATE;VALUE=DATE:20200115,20200116",
+        ]);
+        let at = |month, day, hour| Utc.with_ymd_and_hms(2020, month, day, hour, 0, 0).unwrap();
+        assert_eq!(event.exdates, [at(1, 1, 9), at(2, 1, 9), at(3, 1, 9)]);
+        assert_eq!(event.rdates, [at(1, 15, 0), at(1, 16, 0)]);
+    }
+
+    fn starts(props: &[&str], n: usize) -> Vec<String> {
+        event(props)
+            .first_n_occurrences(n)
+            .iter()
+            .map(|start| start.format("%Y%m%dT%H%M%S").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn every_frequency() {
+        let rule = |dtstart, rrule| starts(&[dtstart, rrule], 10);
+        assert_eq!(
+            rule(
+                "DTSTART:20190513T090000Z",
+                "RRULE:FREQ=SECONDLY;INTERVAL=30;COUNT=3"
+            ),
+            ["20190513T090000", "20190513T090030", "20190513T090100"]
+        );
+        assert_eq!(
+            rule(
+                "DTSTART:20190513T093000Z",
+                "RRULE:FREQ=MINUTELY;INTERVAL=15;BYHOUR=9;COUNT=3"
+            ),
+            ["20190513T093000", "20190513T094500", "20190514T090000"]
+        );
+        assert_eq!(
+            rule(
+                "DTSTART:20190513T090000Z",
+                "RRULE:FREQ=HOURLY;INTERVAL=8;COUNT=4"
+            ),
+            [
+                "20190513T090000",
+                "20190513T170000",
+                "20190514T010000",
+                "20190514T090000"
+            ]
+        );
+        assert_eq!(
+            rule(
+                "DTSTART:20190515T090000Z",
+                "RRULE:FREQ=DAILY;BYDAY=MO,FR;COUNT=3"
+            ),
+            ["20190515T090000", "20190517T090000", "20190520T090000"]
+        );
+        assert_eq!(
+            rule(
+                "DTSTART:20190514T090000Z",
+                "RRULE:FREQ=WEEKLY;BYDAY=TU,TH;UNTIL=20190523T090000Z"
+            ),
+            [
+                "20190514T090000",
+                "20190516T090000",
+                "20190521T090000",
+                "20190523T090000"
+            ]
+        );
+        assert_eq!(
+            rule(
+                "DTSTART:20190531T090000Z",
+                "RRULE:FREQ=MONTHLY;BYDAY=-1FR;COUNT=3"
+            ),
+            ["20190531T090000", "20190628T090000", "20190726T090000"]
+        );
+        assert_eq!(
+            rule(
+                "DTSTART:20160229T090000Z",
+                "RRULE:FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29;COUNT=2"
+            ),
+            ["20160229T090000", "20200229T090000"]
+        );
+    }
+
+    #[test]
+    fn dst_recurrence() {
+        let at = |day, hour| Utc.with_ymd_and_hms(2019, 3, day, hour, 0, 0).unwrap();
+        let event = event(&[
+            "DTSTART;TZID=America/New_York:20190301T090000",
+            "RRULE:FREQ=WEEKLY;COUNT=3",
+        ]);
+        assert_eq!(
+            event.first_n_occurrences(10),
+            [at(1, 14), at(8, 14), at(15, 13)]
+        );
+
+        // floating times are kept as they are
+        let ical = parse(
+            &["X-WR-TIMEZONE:America/New_York"],
+            &["DTSTART:20190301T090000", "RRULE:FREQ=WEEKLY;COUNT=3"],
+        );
+        assert_eq!(
+            ical.events[0].first_n_occurrences(10),
+            [at(1, 9), at(8, 9), at(15, 9)]
+        );
+    }
+
+    #[test]
+    fn fine_frequency_cap() {
+        let event = event(&["DTSTART:20190513T090000Z", "RRULE:FREQ=SECONDLY"]);
+        assert_eq!(event.occurrences().count(), MAX_FINE_PERIODS as usize);
+    }
+
+    #[test]
+    fn rrule_overflow() {
+        for rrule in [
+            "RRULE:FREQ=MINUTELY;INTERVAL=4000000000",
+            "RRULE:FREQ=HOURLY;INTERVAL=4000000000",
+            "RRULE:FREQ=DAILY;INTERVAL=4000000000",
+            "RRULE:FREQ=WEEKLY;INTERVAL=4000000000",
+            "RRULE:FREQ=MONTHLY;INTERVAL=4000000000",
+            "RRULE:FREQ=YEARLY;INTERVAL=4000000000",
+        ] {
+            let event = event(&["DTSTART:20190513T090000Z", rrule]);
+            assert_eq!(
+                starts(&["DTSTART:20190513T090000Z", rrule], 1),
+                ["20190513T090000"]
             );
-            data.push_str("\r\n");
-            data.push_str("LOCATION:");
-            data.push_str(&i.location.as_ref().unwrap());
-            data.push_str("\r\n");
-            data.push_str("SEQUENCE:");
-            data.push_str(&i.sequence.as_ref().unwrap().to_string());
-            data.push_str("\r\n");
-            data.push_str("STATUS:");
-            data.push_str(&i.status.as_ref().unwrap());
-            data.push_str("\r\n");
-            data.push_str("SUMMARY:");
-            data.push_str(&i.summary.as_ref().unwrap());
-            data.push_str("\r\n");
-            data.push_str("TRANSP:");
-            data.push_str(&i.transp.as_ref().unwrap());
-            data.push_str("\r\n");
-            data.push_str("END:VEVENT\r\n");
-        }
-        data.push_str("END:VCALENDAR");
-        match f.write_all(data.as_bytes()) {
-            Ok(_) => Ok(true),
-            Err(e) => Err(e),
+            assert!(event.occurrences().count() < 100, "{}", rrule);
         }
     }
+
+    #[test]
+    fn geo() {
+        let ical = parse(&[], &["GEO:37.386013;-122.082932"]);
+        assert_eq!(ical.events[0].geo, Some((37.386013, -122.082932)));
+        assert!(ical
+            .to_ics_string()
+            .contains("GEO:37.386013;-122.082932\r\n"));
+        assert_eq!(reparse(&ical).events[0].geo, ical.events[0].geo);
+
+        let malformed = ics(&[], &["GEO:somewhere"]);
+        assert_eq!(
+            Calendar::new_from_data(&malformed).unwrap().events[0].geo,
+            None
+        );
+        assert!(Calendar::new_from_data_with_options(&malformed, &strict()).is_err());
+    }
+
+    #[test]
+    fn priority() {
+        let priority = |value: &str| event(&[&format!("PRIORITY:{}", value)]);
+
+        let undefined = priority("0");
+        assert_eq!(undefined.priority, Some(0));
+        assert!(!undefined.is_high_priority() && !undefined.is_low_priority());
+        let highest = priority("1");
+        assert_eq!(highest.priority, Some(1));
+        assert!(highest.is_high_priority());
+        let medium = priority("5");
+        assert_eq!(medium.priority, Some(5));
+        assert!(!medium.is_high_priority() && !medium.is_low_priority());
+        let lowest = priority("9");
+        assert_eq!(lowest.priority, Some(9));
+        assert!(lowest.is_low_priority());
+        assert!(parse(&[], &["PRIORITY:9"])
+            .to_ics_string()
+            .contains("PRIORITY:9\r\n"));
+
+        assert_eq!(priority("10").priority, None);
+        assert_eq!(priority("high").priority, None);
+        let invalid = ics(&[], &["PRIORITY:high"]);
+        assert!(Calendar::new_from_data_with_options(&invalid, &strict()).is_err());
+    }
+
+    #[test]
+    fn percent_complete() {
+        let half_done = event(&["PERCENT-COMPLETE:50"]);
+        assert_eq!(half_done.percent_complete, Some(50));
+        assert_eq!(half_done.completed, None);
+
+        let completed = ["PERCENT-COMPLETE:100", "COMPLETED:20200101T120000Z"];
+        let done = event(&completed);
+        assert_eq!(done.percent_complete, Some(100));
+        assert_eq!(
+            done.completed,
+            Some(Utc.with_ymd_and_hms(2020, 1, 1, 12, 0, 0).unwrap())
+        );
+        assert!(parse(&[], &completed)
+            .to_ics_string()
+            .contains(&completed.join("\r\n")));
+
+        assert_eq!(event(&["PERCENT-COMPLETE:150"]).percent_complete, None);
+        let invalid = ics(&[], &["PERCENT-COMPLETE:150"]);
+        assert!(Calendar::new_from_data_with_options(&invalid, &strict()).is_err());
+    }
+
+    #[test]
+    fn recurrence_id() {
+        let data = ics(
+            &[],
+            &[
+                "DTSTART;TZID=Europe/Berlin:20190506T090000",
+                "DTEND;TZID=Europe/Berlin:20190506T100000",
+                "RRULE:FREQ=WEEKLY;COUNT=3",
+                "END:VEVENT",
+                "BEGIN:VEVENT",
+                "UID:1@example.com",
+                "RECURRENCE-ID;TZID=Europe/Berlin:20190513T090000",
+                "DTSTART;TZID=Europe/Berlin:20190514T140000",
+                "DTEND;TZID=Europe/Berlin:20190514T150000",
+                "SUMMARY:Moved",
+            ],
+        );
+        let ical = Calendar::new_from_data(&data).unwrap();
+        let moved = Utc.with_ymd_and_hms(2019, 5, 13, 7, 0, 0).unwrap();
+        assert_eq!(ical.events[1].recur_id, Some(moved));
+        assert!(ical
+            .to_ics_string()
+            .contains("RECURRENCE-ID:20190513T070000Z\r\n"));
+        assert_eq!(reparse(&ical).events[1].recur_id, Some(moved));
+
+        let start = Utc.with_ymd_and_hms(2019, 5, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap();
+        let starts: Vec<_> = ical
+            .events_between(start, end)
+            .iter()
+            .map(|ev| ev.dtstart.unwrap())
+            .collect();
+        assert_eq!(
+            starts,
+            [
+                Utc.with_ymd_and_hms(2019, 5, 6, 7, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2019, 5, 14, 12, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2019, 5, 20, 7, 0, 0).unwrap(),
+            ]
+        );
+
+        let legacy = data.replace("RECURRENCE-ID", "RECUR-ID");
+        assert_eq!(
+            Calendar::new_from_data(&legacy).unwrap().events[1].recur_id,
+            Some(moved)
+        );
+    }
+
+    #[test]
+    fn tzid_times() {
+        let local = |value| NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").unwrap();
+        let at = |day, hour, min| Utc.with_ymd_and_hms(2019, 3, day, hour, min, 0).unwrap();
+        assert_eq!(
+            local_to_utc(local("20190330T120000"), "Europe/Berlin"),
+            at(30, 11, 0)
+        );
+        assert_eq!(
+            local_to_utc(local("20190331T120000"), "/Europe/Berlin"),
+            at(31, 10, 0)
+        );
+        // skipped by the change to summer time
+        assert_eq!(
+            local_to_utc(local("20190331T023000"), "Europe/Berlin"),
+            at(31, 1, 30)
+        );
+        assert_eq!(
+            local_to_utc(local("20190331T023000"), "Custom Zone"),
+            at(31, 2, 30)
+        );
+
+        let event = event(&[
+            "DTSTART;TZID=America/New_York:20190330T120000",
+            "EXDATE;TZID=America/New_York:20190330T120000,20190331T120000",
+            "RDATE;TZID=America/New_York:20190330T150000Z",
+        ]);
+        assert_eq!(event.dtstart, Some(at(30, 16, 0)));
+        assert_eq!(event.exdates, [at(30, 16, 0), at(31, 16, 0)]);
+        assert_eq!(event.rdates, [at(30, 15, 0)]);
+    }
+
+    #[test]
+    fn event_color() {
+        let ical = parse(&[], &["COLOR:turquoise"]);
+        assert_eq!(ical.events[0].color.as_deref(), Some("turquoise"));
+        assert_eq!(ical.events[0].color_rgb(), Some((64, 224, 208)));
+        assert!(ical.to_ics_string().contains("COLOR:turquoise\r\n"));
+
+        let unknown = ics(&[], &["COLOR:blurple"]);
+        let ical = Calendar::new_from_data(&unknown).unwrap();
+        assert_eq!(ical.events[0].color.as_deref(), Some("blurple"));
+        assert_eq!(ical.events[0].color_rgb(), None);
+        assert!(Calendar::new_from_data_with_options(&unknown, &strict()).is_err());
+    }
+
+    #[test]
+    fn escaped_text() {
+        let ical = parse(
+            &[],
+            &[
+                "SUMMARY:Lunch\\, then review\\; maybe",
+                "DESCRIPTION:First line\\nsecond \\\\ line",
+                "LOCATION:Room 1\\, east wing",
+            ],
+        );
+        let event = &ical.events[0];
+        assert_eq!(event.summary.as_deref(), Some("Lunch, then review; maybe"));
+        assert_eq!(
+            event.description.as_deref(),
+            Some("First line\nsecond \\ line")
+        );
+        assert_eq!(event.location.as_deref(), Some("Room 1, east wing"));
+        assert_eq!(event.escaped_summary(), "Lunch\\, then review\\; maybe");
+        assert_eq!(event.escaped_description(), "First line\\nsecond \\\\ line");
+        assert_eq!(event.escaped_location(), "Room 1\\, east wing");
+
+        let out = ical.to_ics_string();
+        assert!(out.contains("\r\nSUMMARY:Lunch\\, then review\\; maybe\r\n"));
+        assert!(out.contains("\r\nDESCRIPTION:First line\\nsecond \\\\ line\r\n"));
+        assert!(out.contains("\r\nLOCATION:Room 1\\, east wing\r\n"));
+        assert_eq!(reparse(&ical).events[0], *event);
+    }
+
+    #[test]
+    fn event_categories() {
+        let ical = parse(&[], &["CATEGORIES:Work,Rock\\, Paper"]);
+        assert_eq!(ical.events[0].categories, ["Work", "Rock, Paper"]);
+        assert!(ical
+            .to_ics_string()
+            .contains("CATEGORIES:Work,Rock\\, Paper\r\n"));
+        assert_eq!(reparse(&ical).events[0].categories, ["Work", "Rock, Paper"]);
+    }
+
+    #[test]
+    fn contact_comment_resources() {
+        let ical = parse(
+            &[],
+            &[
+                "CONTACT:Jim Dolittle\\, ABC Industries\\; +1-919-555-1234",
+                "COMMENT:Bring the slides",
+                "COMMENT:Coffee is provided",
+                "RESOURCES:Projector,Laptop",
+                "RESOURCES:Room 2\\, east wing",
+            ],
+        );
+        let event = &ical.events[0];
+        assert_eq!(
+            event.contact,
+            ["Jim Dolittle, ABC Industries; +1-919-555-1234"]
+        );
+        assert_eq!(event.comment, ["Bring the slides", "Coffee is provided"]);
+        assert_eq!(
+            event.resources,
+            ["Projector", "Laptop", "Room 2, east wing"]
+        );
+
+        let out = ical.to_ics_string();
+        assert!(out.contains("CONTACT:Jim Dolittle\\, ABC Industries\\; +1-919-555-1234\r\n"));
+        assert!(out.contains("RESOURCES:Projector,Laptop,Room 2\\, east wing\r\n"));
+        assert_eq!(reparse(&ical).events[0], *event);
+    }
+
+    #[test]
+    fn attendees() {
+        let jane = "ATTENDEE;CN=Jane Doe;ROLE=REQ-PARTICIPANT;PARTSTAT=ACCEPTED:mailto:jane@x.com";
+        let bob = "ATTENDEE;ROLE=OPT-PARTICIPANT;PARTSTAT=NEEDS-ACTION;RSVP=TRUE:mailto:bob@x.com";
+        let ical = parse(&[], &[jane, bob]);
+        let attendees = &ical.events[0].attendees;
+        assert_eq!(attendees.len(), 2);
+        assert_eq!(attendees[0].cn.as_deref(), Some("Jane Doe"));
+        assert_eq!(attendees[0].partstat.as_deref(), Some("ACCEPTED"));
+        assert_eq!(attendees[0].rsvp, None);
+        assert_eq!(attendees[0].email, "jane@x.com");
+        assert_eq!(attendees[1].cn, None);
+        assert_eq!(attendees[1].role.as_deref(), Some("OPT-PARTICIPANT"));
+        assert_eq!(attendees[1].rsvp, Some(true));
+
+        let out = unfold(ical.to_ics_string().as_bytes());
+        assert!(out.iter().any(|line| line == jane));
+        assert!(out.iter().any(|line| line == bob));
+    }
+
+    #[test]
+    fn nested_component() {
+        let ical = parse(
+            &[],
+            &[
+                "BEGIN:VLOCATION",
+                "UID:loc@example.com",
+                "NAME:Room",
+                "END:VLOCATION",
+                "SUMMARY:Sync",
+            ],
+        );
+        let event = &ical.events[0];
+        assert_eq!(event.uid.as_deref(), Some("1@example.com"));
+        assert_eq!(event.summary.as_deref(), Some("Sync"));
+        assert!(event.extra.is_empty());
+        assert!(!ical.to_ics_string().contains("VLOCATION"));
+    }
+
+    #[test]
+    fn extra_properties() {
+        let ical = parse(
+            &["X-APPLE-CALENDAR-COLOR:#FF2968"],
+            &[
+                "X-APPLE-TRAVEL-ADVISORY-BEHAVIOR:AUTOMATIC",
+                "X-MICROSOFT-CDO-BUSYSTATUS;X-FOO=bar:BUSY",
+            ],
+        );
+        assert_eq!(
+            ical.events[0].extra[0],
+            (
+                "X-APPLE-TRAVEL-ADVISORY-BEHAVIOR".to_string(),
+                "AUTOMATIC".to_string()
+            )
+        );
+
+        let out = ical.to_ics_string();
+        assert!(out.contains("X-APPLE-CALENDAR-COLOR:#FF2968\r\n"));
+        assert!(out.contains("X-APPLE-TRAVEL-ADVISORY-BEHAVIOR:AUTOMATIC\r\n"));
+        assert!(out.contains("X-MICROSOFT-CDO-BUSYSTATUS;X-FOO=bar:BUSY\r\n"));
+    }
+
+    #[test]
+    fn calendar_name() {
+        let ical = parse(&["NAME:Company Holidays", "X-WR-CALNAME:Holidays"], &[]);
+        assert_eq!(ical.name.as_deref(), Some("Company Holidays"));
+        let out = ical.to_ics_string();
+        assert!(out.contains("NAME:Company Holidays\r\nX-WR-CALNAME:Holidays\r\n"));
+        assert_eq!(reparse(&ical).name, ical.name);
+    }
+
+    #[test]
+    fn calendar_color() {
+        let ical = parse(&["COLOR:turquoise"], &[]);
+        assert_eq!(ical.color.as_deref(), Some("turquoise"));
+        assert!(ical.to_ics_string().contains("\r\nCOLOR:turquoise\r\n"));
+
+        let ical = parse(&["X-APPLE-CALENDAR-COLOR:#FF2968"], &[]);
+        assert_eq!(ical.color.as_deref(), Some("#FF2968"));
+        assert!(ical.to_ics_string().contains("\r\nCOLOR:#FF2968\r\n"));
+
+        let both = parse(&["COLOR:turquoise", "X-APPLE-CALENDAR-COLOR:#FF2968"], &[]);
+        assert_eq!(both.color.as_deref(), Some("turquoise"));
+    }
+
+    #[test]
+    fn refresh_interval() {
+        let ical = parse(&["REFRESH-INTERVAL;VALUE=DURATION:PT6H"], &[]);
+        assert_eq!(ical.refresh_interval, Some(Duration::hours(6)));
+        assert!(ical
+            .to_ics_string()
+            .contains("REFRESH-INTERVAL;VALUE=DURATION:PT6H\r\n"));
+
+        let ical = parse(&["REFRESH-INTERVAL;VALUE=DURATION:P1D"], &[]);
+        assert_eq!(ical.refresh_interval, Some(Duration::days(1)));
+        assert!(ical
+            .to_ics_string()
+            .contains("REFRESH-INTERVAL;VALUE=DURATION:P1D\r\n"));
+    }
+
+    #[test]
+    fn unusual_prodid() {
+        let prodid = "-//Example Corp.//CalDAV Server 1.0: build 2:3//EN";
+        let data = format!(
+            "BEGIN:VCALENDAR\r\nPRODID:{}\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n",
+            prodid
+        );
+        let ical = Calendar::new_from_data(&data).unwrap();
+        assert_eq!(ical.prodid, prodid);
+        assert_eq!(ical.version, "2.0");
+
+        let out = ical.to_ics_string();
+        assert!(out.contains(&format!("\r\nPRODID:{}\r\n", prodid)));
+        assert_eq!(reparse(&ical).prodid, prodid);
+    }
+
+    #[test]
+    fn version() {
+        let data = |version: &str| {
+            format!(
+                "BEGIN:VCALENDAR\r\nPRODID:-//Test//EN\r\n{}END:VCALENDAR\r\n",
+                version
+            )
+        };
+
+        let (ical, warnings) = Calendar::parse_verbose(&data("VERSION:2.0;2.0\r\n"));
+        assert_eq!(ical.unwrap().version, "2.0");
+        assert!(warnings.is_empty());
+
+        let (ical, warnings) = Calendar::parse_verbose(&data("VERSION:1.0\r\n"));
+        assert_eq!(ical.unwrap().version, "1.0");
+        assert_eq!(warnings[0].message, "unsupported version: 1.0");
+
+        // a missing version is assumed to be 2.0 when parsing leniently
+        let options = ParseOptions {
+            mode: ParseMode::Lenient,
+            ..ParseOptions::default()
+        };
+        let ical = Calendar::new_from_data_with_options(&data(""), &options).unwrap();
+        assert_eq!(ical.version, "2.0");
+    }
+
+    #[test]
+    fn calendar_categories() {
+        let ical = parse(
+            &["CATEGORIES:Holidays,Rock\\, Paper", "CATEGORIES:Public"],
+            &["CATEGORIES:Event"],
+        );
+        assert_eq!(ical.categories, ["Holidays", "Rock, Paper", "Public"]);
+        assert_eq!(ical.events[0].categories, ["Event"]);
+
+        let mut empty = Calendar::create("-//Test//EN", "2.0", "GREGORIAN", "PUBLISH", "", "UTC");
+        assert_eq!(empty.x_wr_calname, None);
+        empty.categories = ical.categories.clone();
+        let out = empty.to_ics_string();
+        assert!(!out.contains("X-WR-CALNAME"));
+        assert!(out.contains("CATEGORIES:Holidays,Rock\\, Paper,Public\r\n"));
+        assert_eq!(reparse(&empty).categories, ical.categories);
+    }
 }
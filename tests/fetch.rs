@@ -0,0 +1,149 @@
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use flate2::{write::GzEncoder, Compression};
+use web_ical::{Calendar, CalendarFetch, FetchOptions};
+
+const FEED: &[u8] = b"BEGIN:VCALENDAR\r\n\
+                      PRODID:-//Test//EN\r\n\
+                      VERSION:2.0\r\n\
+                      BEGIN:VEVENT\r\n\
+                      UID:1@example.com\r\n\
+                      END:VEVENT\r\n\
+                      END:VCALENDAR\r\n";
+
+/// Answer `requests` requests on a local port with the response `respond` builds from the
+/// request head, returning the server's base URL.
+fn serve<F>(requests: usize, mut respond: F) -> String
+where
+    F: FnMut(&str) -> Vec<u8> + Send + 'static,
+{
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for _ in 0..requests {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            let response = respond(&String::from_utf8_lossy(&request));
+            stream.write_all(&response).unwrap();
+        }
+    });
+    url
+}
+
+/// A response with the status line `status`, the extra header lines `headers` and `body`.
+fn response(status: &str, headers: &str, body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        headers,
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+    gzip.write_all(data).unwrap();
+    gzip.finish().unwrap()
+}
+
+#[tokio::test]
+async fn gzip_encoding() {
+    let url = serve(1, |_| {
+        let headers = "Content-Type: text/calendar\r\nContent-Encoding: gzip\r\n";
+        response("200 OK", headers, &gzip(FEED))
+    });
+    let ical = Calendar::new(&format!("{}/feed.ics", url)).await.unwrap();
+    assert_eq!(ical.events[0].uid.as_deref(), Some("1@example.com"));
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn unlabeled_gzip() {
+    let url = serve(1, |_| {
+        let headers = "Content-Type: application/octet-stream\r\n";
+        response("200 OK", headers, &gzip(FEED))
+    });
+    let ical = Calendar::new(&format!("{}/feed.ics.gz", url))
+        .await
+        .unwrap();
+    assert_eq!(ical, Calendar::new_from_bytes(FEED).unwrap());
+}
+
+#[tokio::test]
+async fn redirects() {
+    let redirect = |_: &str| response("302 Found", "Location: /feed.ics\r\n", b"");
+    let options = FetchOptions {
+        max_redirects: 1,
+        ..FetchOptions::default()
+    };
+
+    let mut requests = 0;
+    let url = serve(2, move |_| {
+        requests += 1;
+        if requests == 1 {
+            redirect("")
+        } else {
+            response("200 OK", "", FEED)
+        }
+    });
+    let ical = Calendar::new_with_options(&format!("{}/short", url), &options).await;
+    assert_eq!(ical.unwrap().prodid, "-//Test//EN");
+
+    // a redirect loop is given up on
+    let url = serve(2, redirect);
+    assert!(Calendar::new_with_options(&url, &options).await.is_err());
+}
+
+#[tokio::test]
+async fn timeout() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/feed.ics", listener.local_addr().unwrap());
+    let options = FetchOptions {
+        timeout: Some(Duration::from_millis(200)),
+        ..FetchOptions::default()
+    };
+    assert!(Calendar::new_with_options(&url, &options).await.is_err());
+}
+
+#[tokio::test]
+async fn conditional() {
+    let url = serve(3, |request| {
+        if request
+            .to_ascii_lowercase()
+            .contains("if-none-match: \"v1\"\r\n")
+        {
+            response("304 Not Modified", "", b"")
+        } else {
+            response("200 OK", "ETag: \"v1\"\r\n", FEED)
+        }
+    });
+    let url = format!("{}/feed.ics", url);
+
+    let etag = match Calendar::new_conditional(&url, None).await.unwrap() {
+        CalendarFetch::Modified { calendar, etag } => {
+            assert_eq!(calendar.prodid, "-//Test//EN");
+            etag
+        }
+        CalendarFetch::NotModified => panic!("nothing to compare with yet"),
+    };
+    assert_eq!(etag.as_deref(), Some("\"v1\""));
+
+    let fetch = Calendar::new_conditional(&url, etag.as_deref())
+        .await
+        .unwrap();
+    assert_eq!(fetch, CalendarFetch::NotModified);
+
+    let fetch = Calendar::new_conditional(&url, Some("\"v0\""))
+        .await
+        .unwrap();
+    assert!(matches!(fetch, CalendarFetch::Modified { .. }));
+}